@@ -0,0 +1,72 @@
+use crate::configs::AccountKeys;
+use crate::error::DojoResult;
+use crate::events::ContractEvent;
+use crate::models::{Door, Game, Object};
+use crate::resources::{AccountInfo, DojoConnectionStatus};
+use crate::retry::RetryAttempted;
+use crate::transactions::QueuedTransaction;
+use async_trait::async_trait;
+use bevy::prelude::*;
+use starknet::core::types::FieldElement;
+use std::sync::Arc;
+
+/// The calls the game makes against the `actions` contract and the models it reads back.
+///
+/// Gameplay systems only talk to a [`Backend`], so the Starknet client in `dojo.rs`
+/// can be swapped for another implementation without touching them.
+///
+/// Actions return the events the contract emitted while running them.
+#[async_trait]
+pub trait GameBackend: Send + Sync {
+    async fn initialise(&self, turns_remaining: u64) -> DojoResult<Vec<ContractEvent>>;
+
+    // descriptions are plain text of any length
+    async fn spawn_object(
+        &self,
+        objects_id: Vec<FieldElement>,
+        objects_description: Vec<String>,
+    ) -> DojoResult<Vec<ContractEvent>>;
+
+    async fn interact(&self, object_id: FieldElement) -> DojoResult<Vec<ContractEvent>>;
+
+    async fn escape(&self, secret: FieldElement) -> DojoResult<Vec<ContractEvent>>;
+
+    // signs the next actions with `keys`, model reads then use its address
+    async fn switch_account(&self, keys: AccountKeys) -> DojoResult<()>;
+
+    // address, nonce and fee-token balance of the account signing the actions
+    async fn account_info(&self) -> DojoResult<AccountInfo>;
+
+    // the caller's `Game` model
+    async fn game(&self) -> DojoResult<Game>;
+
+    // the caller's `Object` model for `object_id`
+    async fn object(&self, object_id: FieldElement) -> DojoResult<Object>;
+
+    // the caller's `Door` in game `game_id`
+    async fn door(&self, game_id: u32) -> DojoResult<Door>;
+
+    // chain id and latest block of the node, backends without one are always connected
+    async fn health(&self) -> DojoConnectionStatus {
+        DojoConnectionStatus::Connected { block_number: 0 }
+    }
+
+    // objects the followed player inspected since the last call, only spectators
+    // follow another player
+    async fn inspected_objects(&self) -> DojoResult<Vec<FieldElement>> {
+        Ok(Vec::new())
+    }
+
+    // transactions still in flight and the last finished ones
+    fn transactions(&self) -> Vec<QueuedTransaction> {
+        Vec::new()
+    }
+
+    // retries made since the last call
+    fn take_retries(&self) -> Vec<RetryAttempted> {
+        Vec::new()
+    }
+}
+
+#[derive(Resource, Clone, Deref)]
+pub struct Backend(pub Arc<dyn GameBackend>);
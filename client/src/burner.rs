@@ -0,0 +1,115 @@
+//! Throwaway accounts for local devnets, so that every player gets their own `Game`.
+//!
+//! The burner's key is generated on first launch and kept in a local file. Its account is
+//! deployed, with funds from the configured account, whenever the chain does not know
+//! it yet, for example after Katana was restarted.
+
+use crate::configs::AccountKeys;
+use crate::dojo::DojoEnv;
+use crate::error::DojoError;
+use serde::{Deserialize, Serialize};
+use starknet::{core::types::FieldElement, signers::SigningKey};
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// Where the burner is kept and how it is deployed.
+#[derive(Clone, Debug)]
+pub struct BurnerSettings {
+    pub path: PathBuf,
+    // class of the OpenZeppelin account contract declared on the devnet
+    pub class_hash: FieldElement,
+    // sent from the configured account before deploying, in the fee token's base unit
+    pub funding: FieldElement,
+}
+
+#[derive(Debug)]
+pub enum BurnerError {
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, String),
+    Deploy(DojoError),
+}
+
+impl std::fmt::Display for BurnerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BurnerError::Io(path, e) => write!(f, "cannot access {}: {}", path.display(), e),
+            BurnerError::Parse(path, e) => write!(f, "cannot parse {}: {}", path.display(), e),
+            BurnerError::Deploy(e) => write!(f, "cannot deploy the burner account: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for BurnerError {}
+
+impl From<BurnerError> for DojoError {
+    fn from(e: BurnerError) -> Self {
+        match e {
+            BurnerError::Deploy(e) => e,
+            e => DojoError::burner(e),
+        }
+    }
+}
+
+// the burner file, plaintext like the dev keys in `dojo.toml`
+#[derive(Serialize, Deserialize)]
+struct BurnerFile {
+    private_key: FieldElement,
+}
+
+/// The burner saved at `settings.path`, or a new one, deployed through `env`.
+pub async fn load_or_create(
+    env: &DojoEnv,
+    settings: &BurnerSettings,
+) -> Result<AccountKeys, BurnerError> {
+    let secret_key = match load(&settings.path)? {
+        Some(secret_key) => secret_key,
+        None => {
+            // saved before deploying, funds sent to it are never lost with the key
+            let secret_key = SigningKey::from_random().secret_scalar();
+            save(&settings.path, secret_key)?;
+            println!("New burner account saved to {}.", settings.path.display());
+            secret_key
+        }
+    };
+
+    let address = env
+        .deploy_account(settings.class_hash, secret_key, settings.funding)
+        .await
+        .map_err(BurnerError::Deploy)?;
+
+    Ok(AccountKeys {
+        address,
+        secret_key,
+    })
+}
+
+fn load(path: &Path) -> Result<Option<FieldElement>, BurnerError> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(BurnerError::Io(path.into(), e)),
+    };
+
+    let file: BurnerFile = serde_json::from_str(&content)
+        .map_err(|e| BurnerError::Parse(path.into(), e.to_string()))?;
+    Ok(Some(file.private_key))
+}
+
+fn save(path: &Path, private_key: FieldElement) -> Result<(), BurnerError> {
+    let content = serde_json::to_string_pretty(&BurnerFile { private_key })
+        .map_err(|e| BurnerError::Parse(path.into(), e.to_string()))?;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    // readable by its owner only
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .map_err(|e| BurnerError::Io(path.into(), e))
+}
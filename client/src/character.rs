@@ -1,3 +1,4 @@
+use crate::resources::Spectating;
 use bevy::prelude::*;
 use bevy_inspector_egui::InspectorOptions;
 
@@ -6,7 +7,13 @@ pub struct CharacterPlugin;
 impl Plugin for CharacterPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Startup, setup)
-            .add_systems(Update, (animate_sprite, character_movement))
+            .add_systems(
+                Update,
+                (
+                    animate_sprite,
+                    character_movement.run_if(not(resource_exists::<Spectating>())),
+                ),
+            )
             .register_type::<Player>(); // for new types
     }
 }
@@ -0,0 +1,173 @@
+//! Cairo strings as felts: short strings and arrays of them, and model entities packed
+//! by dojo-core.
+//!
+//! A short string holds up to 31 bytes in a single felt. Longer strings are split into
+//! 31 byte words and serialized as an `Array<felt252>` of short strings
+//! (`[len, ...words]`), Cairo 2.2 has no `ByteArray`.
+
+use starknet::core::types::FieldElement;
+
+/// Bytes held by one felt.
+pub const WORD_LEN: usize = 31;
+
+// bits of a packed felt holding values
+const PACKED_BITS: u32 = 251;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CodecError {
+    // the string does not fit in a short string
+    TooLong(usize),
+    // fewer felts than the layout announces
+    Truncated(&'static str),
+    // a length or word that cannot be right
+    Invalid(&'static str),
+    NotUtf8,
+}
+
+impl std::fmt::Display for CodecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodecError::TooLong(len) => write!(
+                f,
+                "{len} bytes do not fit in a short string of {WORD_LEN} bytes"
+            ),
+            CodecError::Truncated(layout) => write!(f, "{layout} is cut short"),
+            CodecError::Invalid(what) => write!(f, "invalid {what}"),
+            CodecError::NotUtf8 => f.write_str("string is not valid UTF-8"),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+pub fn encode_short(value: &str) -> Result<FieldElement, CodecError> {
+    if value.len() > WORD_LEN {
+        return Err(CodecError::TooLong(value.len()));
+    }
+    Ok(word(value.as_bytes()))
+}
+
+pub fn decode_short(felt: FieldElement) -> Result<String, CodecError> {
+    utf8(word_bytes(felt, None)?)
+}
+
+/// Serializes `value` as an `Array<felt252>` of short strings.
+pub fn encode_felt_array(value: &str) -> Vec<FieldElement> {
+    let words: Vec<FieldElement> = value.as_bytes().chunks(WORD_LEN).map(word).collect();
+
+    let mut felts = Vec::with_capacity(words.len() + 1);
+    felts.push(words.len().into());
+    felts.extend(words);
+    felts
+}
+
+/// Reads an `Array<felt252>` of short strings at the start of `felts`, returns the
+/// joined string and the felts it used.
+pub fn decode_felt_array(felts: &[FieldElement]) -> Result<(String, usize), CodecError> {
+    const LAYOUT: &str = "felt array";
+
+    let len = length(felts.first(), LAYOUT)?;
+    let words = felts.get(1..=len).ok_or(CodecError::Truncated(LAYOUT))?;
+
+    let mut bytes = Vec::with_capacity(len * WORD_LEN);
+    for felt in words {
+        bytes.extend(word_bytes(*felt, None)?);
+    }
+    Ok((utf8(bytes)?, len + 1))
+}
+
+// up to 31 bytes, big endian
+/// The values of a model entity as dojo-core stores them, each taking the number of
+/// bits `layout` gives it. Values are packed from the lowest bit of a felt, one that
+/// does not fit in what is left of it starts the next felt.
+pub fn unpack(
+    packed: &[FieldElement],
+    layout: &[FieldElement],
+) -> Result<Vec<FieldElement>, CodecError> {
+    let mut felts = packed.iter().map(FieldElement::to_bytes_be);
+    let mut current = None;
+    let mut offset = 0;
+
+    layout
+        .iter()
+        .map(|size| {
+            let size = packed_bits(*size)?;
+            if current.is_none() || offset + size > PACKED_BITS {
+                current = felts.next();
+                offset = 0;
+            }
+            let felt = current
+                .as_ref()
+                .ok_or(CodecError::Truncated("packed entity"))?;
+
+            let mut value = [0_u8; 32];
+            for bit in 0..size {
+                let from = (offset + bit) as usize;
+                if (felt[31 - from / 8] >> (from % 8)) & 1 == 1 {
+                    value[31 - bit as usize / 8] |= 1 << (bit % 8);
+                }
+            }
+            offset += size;
+            FieldElement::from_bytes_be(&value).map_err(|_| CodecError::Invalid("packed value"))
+        })
+        .collect()
+}
+
+/// Felts taken by the values of `layout` once packed, see [`unpack`].
+pub fn packed_size(layout: &[FieldElement]) -> Result<usize, CodecError> {
+    let mut felts = 0;
+    let mut offset = 0;
+    for size in layout {
+        let size = packed_bits(*size)?;
+        if felts == 0 || offset + size > PACKED_BITS {
+            felts += 1;
+            offset = 0;
+        }
+        offset += size;
+    }
+    Ok(felts)
+}
+
+fn packed_bits(size: FieldElement) -> Result<u32, CodecError> {
+    u32::try_from(size)
+        .ok()
+        .filter(|size| *size <= PACKED_BITS)
+        .ok_or(CodecError::Invalid("layout size"))
+}
+
+fn word(bytes: &[u8]) -> FieldElement {
+    let mut buffer = [0u8; 32];
+    buffer[32 - bytes.len()..].copy_from_slice(bytes);
+    // 31 bytes are always below the field modulus
+    FieldElement::from_bytes_be(&buffer).unwrap()
+}
+
+// the last `len` bytes of `felt`, or its bytes without leading zeros
+fn word_bytes(felt: FieldElement, len: Option<usize>) -> Result<Vec<u8>, CodecError> {
+    let bytes = felt.to_bytes_be();
+    if bytes[0] != 0 {
+        return Err(CodecError::Invalid("word of more than 31 bytes"));
+    }
+
+    let start = match len {
+        Some(len) => {
+            if bytes[..32 - len].iter().any(|&byte| byte != 0) {
+                return Err(CodecError::Invalid("word longer than its length"));
+            }
+            32 - len
+        }
+        None => bytes.iter().position(|&byte| byte != 0).unwrap_or(32),
+    };
+    Ok(bytes[start..].to_vec())
+}
+
+fn length(felt: Option<&FieldElement>, layout: &'static str) -> Result<usize, CodecError> {
+    let felt = felt.ok_or(CodecError::Truncated(layout))?;
+    u32::try_from(*felt)
+        .map(|len| len as usize)
+        .map_err(|_| CodecError::Invalid("length"))
+}
+
+fn utf8(bytes: Vec<u8>) -> Result<String, CodecError> {
+    String::from_utf8(bytes).map_err(|_| CodecError::NotUtf8)
+}
@@ -1,10 +1,25 @@
+use crate::burner::BurnerSettings;
+use crate::codec;
+use crate::fees::FeePolicy;
+use crate::manifest::{DeploymentManifest, ManifestError};
+use crate::retry::RetryPolicy;
+use bevy::prelude::*;
+use serde::Deserialize;
+use starknet::{core::types::FieldElement, signers::SigningKey};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+use url::Url;
+
 // DOJO Settings
 pub const JSON_RPC_ENDPOINT: &str = "http://0.0.0.0:5050";
 
-// katana account 0
+// katana account 0, its key has to be set in the settings file or the environment
 pub const ACCOUNT_ADDRESS: &str =
     "0x517ececd29116499f4a1b64b094da79ba08dfd54a3edaa316134c41f8160973";
-pub const ACCOUNT_SECRET_KEY: &str = "0x1800000000300000180000000000030000000000003006001800006600";
 
 // world
 pub const WORLD_ADDRESS: &str = "0x13dfc87155d415ae384a35ba4333dfe160645ad7c83dc8b5812bd7ade9d69d6";
@@ -12,4 +27,535 @@ pub const ACTIONS_ADDRESS: &str =
     "0x47c92218dfdaac465ad724f028f0f075b1c05c9ff9555d0e426c025e45c035";
 pub const DOJO_SYNC_INTERVAL: f32 = 1.0;
 
+// node health, the chain id is a short string or a hex felt
+pub const CHAIN_ID: &str = "KATANA";
+pub const HEALTH_CHECK_INTERVAL: f32 = 5.0;
+pub const HEALTH_SLOW_RESPONSE: Duration = Duration::from_secs(2);
+
+// transactions
+pub const RECEIPT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+pub const RECEIPT_TIMEOUT: Duration = Duration::from_secs(30);
+
+// fees, the max fee is the estimate times the multiplier, optionally capped (in wei)
+pub const FEE_MULTIPLIER: f64 = 1.5;
+pub const FEE_TOKEN_ADDRESS: &str =
+    "0x49d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7";
+
+// burner accounts, see `burner.rs`. The class is Katana's OpenZeppelin account and the
+// funding 0.1 ETH.
+pub const BURNER_PATH: &str = "burner.json";
+pub const BURNER_CLASS_HASH: &str =
+    "0x04d07e40e93398ed3c76981e72dd1fd22557a78ce36c0515f679e27f0bb5bc5f";
+pub const BURNER_FUNDING: u64 = 100_000_000_000_000_000;
+
+// retries after a network failure
+pub const RETRY_ATTEMPTS: u32 = 3;
+pub const RETRY_BASE_DELAY_MS: u64 = 250;
+pub const RETRY_MAX_DELAY_MS: u64 = 4000;
+
+// settings file, relative to the working directory unless overridden by `DOJO_CONFIG`
+pub const DOJO_CONFIG_FILE: &str = "dojo.toml";
+
 // Game Settings
+pub const STARTING_TURNS: u64 = 10;
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, String),
+    InvalidUrl(&'static str, String),
+    InvalidFelt(&'static str, String),
+    InvalidNumber(&'static str, String),
+    Manifest(ManifestError),
+    ManifestMismatch(&'static str, FieldElement, FieldElement),
+    Conflict(&'static str, &'static str),
+    Keystore(PathBuf, String),
+    // a plaintext key without `dev_plaintext_keys`, or no key at all
+    NoKeystore(&'static str, &'static str),
+    // no key at all, with `dev_plaintext_keys`
+    NoKey(&'static str, &'static str),
+    // a local devnet setting without `dev_plaintext_keys`
+    DevOnly(&'static str),
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Io(path, e) => write!(f, "cannot read {}: {}", path.display(), e),
+            ConfigError::Parse(path, e) => write!(f, "cannot parse {}: {}", path.display(), e),
+            ConfigError::InvalidUrl(key, value) => write!(f, "`{key}` is not a valid url: {value}"),
+            ConfigError::InvalidFelt(key, value) => {
+                write!(f, "`{key}` is not a valid felt: {value}")
+            }
+            ConfigError::InvalidNumber(key, value) => {
+                write!(f, "`{key}` is not a valid number: {value}")
+            }
+            ConfigError::Manifest(e) => write!(f, "{e}"),
+            ConfigError::ManifestMismatch(key, configured, deployed) => write!(
+                f,
+                "`{key}` is set to {configured:#x} but the manifest deploys it at {deployed:#x}"
+            ),
+            ConfigError::Conflict(a, b) => write!(f, "`{a}` and `{b}` cannot both be set"),
+            ConfigError::Keystore(path, e) => {
+                write!(f, "cannot unlock keystore {}: {}", path.display(), e)
+            }
+            ConfigError::NoKeystore(plaintext, keystore) => write!(
+                f,
+                "`{keystore}` is not set, `{plaintext}` is only accepted with `dev_plaintext_keys = true`"
+            ),
+            ConfigError::NoKey(plaintext, keystore) => {
+                write!(f, "no signing key, set either `{plaintext}` or `{keystore}`")
+            }
+            ConfigError::DevOnly(key) => write!(
+                f,
+                "`{key}` is for local devnets, it is only accepted with `dev_plaintext_keys = true`"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+// values as written in the settings file, every field is optional
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct RawDojoConfig {
+    json_rpc_endpoint: Option<String>,
+    account_address: Option<String>,
+    account_secret_key: Option<String>,
+    keystore_path: Option<PathBuf>,
+    dev_plaintext_keys: Option<bool>,
+    world_address: Option<String>,
+    actions_address: Option<String>,
+    manifest_path: Option<PathBuf>,
+    sync_interval: Option<f32>,
+    chain_id: Option<String>,
+    health_check_interval: Option<f32>,
+    rpc_record: Option<PathBuf>,
+    rpc_replay: Option<PathBuf>,
+    accounts: Vec<RawAccount>,
+    retry_attempts: Option<u32>,
+    retry_base_delay_ms: Option<u64>,
+    retry_max_delay_ms: Option<u64>,
+    fee_multiplier: Option<f64>,
+    max_fee: Option<u64>,
+    fee_token_address: Option<String>,
+    burner: Option<bool>,
+    burner_path: Option<PathBuf>,
+    burner_class_hash: Option<String>,
+    burner_funding: Option<u64>,
+}
+
+// an `[[accounts]]` entry
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawAccount {
+    address: String,
+    secret_key: Option<String>,
+    keystore: Option<PathBuf>,
+}
+
+impl RawDojoConfig {
+    fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let content = fs::read_to_string(path).map_err(|e| ConfigError::Io(path.into(), e))?;
+
+        if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&content)
+                .map_err(|e| ConfigError::Parse(path.into(), e.to_string()))
+        } else {
+            toml::from_str(&content).map_err(|e| ConfigError::Parse(path.into(), e.to_string()))
+        }
+    }
+
+    // `DOJO_*` environment variables take precedence over the file
+    fn apply_env(&mut self) -> Result<(), ConfigError> {
+        let var = |name: &str| env::var(name).ok().filter(|value| !value.is_empty());
+
+        if let Some(value) = var("DOJO_RPC_URL") {
+            self.json_rpc_endpoint = Some(value);
+        }
+        if let Some(value) = var("DOJO_ACCOUNT_ADDRESS") {
+            self.account_address = Some(value);
+        }
+        if let Some(value) = var("DOJO_PRIVATE_KEY") {
+            self.account_secret_key = Some(value);
+        }
+        if let Some(value) = var("DOJO_KEYSTORE") {
+            self.keystore_path = Some(value.into());
+        }
+        if let Some(value) = parse_env("DOJO_DEV_KEYS")? {
+            self.dev_plaintext_keys = Some(value);
+        }
+        if let Some(value) = var("DOJO_WORLD_ADDRESS") {
+            self.world_address = Some(value);
+        }
+        if let Some(value) = var("DOJO_ACTIONS_ADDRESS") {
+            self.actions_address = Some(value);
+        }
+        if let Some(value) = var("DOJO_MANIFEST") {
+            self.manifest_path = Some(value.into());
+        }
+        if let Some(value) = parse_env("DOJO_SYNC_INTERVAL")? {
+            self.sync_interval = Some(value);
+        }
+        if let Some(value) = var("DOJO_CHAIN_ID") {
+            self.chain_id = Some(value);
+        }
+        if let Some(value) = parse_env("DOJO_HEALTH_INTERVAL")? {
+            self.health_check_interval = Some(value);
+        }
+        if let Some(value) = var("DOJO_RPC_RECORD") {
+            self.rpc_record = Some(value.into());
+        }
+        if let Some(value) = var("DOJO_RPC_REPLAY") {
+            self.rpc_replay = Some(value.into());
+        }
+        if let Some(value) = parse_env("DOJO_RETRY_ATTEMPTS")? {
+            self.retry_attempts = Some(value);
+        }
+        if let Some(value) = parse_env("DOJO_RETRY_BASE_DELAY_MS")? {
+            self.retry_base_delay_ms = Some(value);
+        }
+        if let Some(value) = parse_env("DOJO_RETRY_MAX_DELAY_MS")? {
+            self.retry_max_delay_ms = Some(value);
+        }
+        if let Some(value) = parse_env("DOJO_FEE_MULTIPLIER")? {
+            self.fee_multiplier = Some(value);
+        }
+        if let Some(value) = parse_env("DOJO_MAX_FEE")? {
+            self.max_fee = Some(value);
+        }
+        if let Some(value) = var("DOJO_FEE_TOKEN") {
+            self.fee_token_address = Some(value);
+        }
+        if let Some(value) = parse_env("DOJO_BURNER")? {
+            self.burner = Some(value);
+        }
+        if let Some(value) = var("DOJO_BURNER_PATH") {
+            self.burner_path = Some(value.into());
+        }
+        Ok(())
+    }
+}
+
+/// A local account the game can play as.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AccountKeys {
+    pub address: FieldElement,
+    pub secret_key: FieldElement,
+}
+
+/// Connection settings for the Dojo world, loaded once at startup.
+#[derive(Resource, Clone, Debug)]
+pub struct DojoConfig {
+    pub json_rpc_endpoint: Url,
+    pub account_address: FieldElement,
+    pub account_secret_key: FieldElement,
+    pub world_address: FieldElement,
+    pub actions_address: FieldElement,
+    pub sync_interval: f32,
+    // the node is reported as `WrongChain` when it answers with another id
+    pub chain_id: FieldElement,
+    pub health_check_interval: f32,
+    // JSON-RPC traffic is written to this file, see `transport.rs`
+    pub rpc_record: Option<PathBuf>,
+    // JSON-RPC answers come from this recording instead of the node
+    pub rpc_replay: Option<PathBuf>,
+    pub retry: RetryPolicy,
+    pub fee: FeePolicy,
+    // a burner replaces the configured account, which then only funds it
+    pub burner: Option<BurnerSettings>,
+    // accounts to switch between, the one above first
+    pub accounts: Vec<AccountKeys>,
+}
+
+impl DojoConfig {
+    /// Loads the settings file (`DOJO_CONFIG` or `dojo.toml`) and applies the
+    /// environment overrides. A missing default file falls back to the constants above.
+    /// When `manifest_path` is set the world and actions addresses come from the
+    /// sozo manifest instead.
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::load_with(false)
+    }
+
+    /// Same as [`load`](Self::load), for spectators. No signing key is read, they are
+    /// all left at zero.
+    pub fn load_read_only() -> Result<Self, ConfigError> {
+        Self::load_with(true)
+    }
+
+    fn load_with(read_only: bool) -> Result<Self, ConfigError> {
+        let (path, required) = match env::var("DOJO_CONFIG") {
+            Ok(path) => (PathBuf::from(path), true),
+            Err(_) => (PathBuf::from(DOJO_CONFIG_FILE), false),
+        };
+
+        let mut raw = if required || path.exists() {
+            RawDojoConfig::from_file(&path)?
+        } else {
+            RawDojoConfig::default()
+        };
+        raw.apply_env()?;
+
+        Self::from_raw(raw, read_only)
+    }
+
+    fn from_raw(raw: RawDojoConfig, read_only: bool) -> Result<Self, ConfigError> {
+        let json_rpc_endpoint = raw
+            .json_rpc_endpoint
+            .unwrap_or_else(|| JSON_RPC_ENDPOINT.to_string());
+        let json_rpc_endpoint = Url::parse(&json_rpc_endpoint)
+            .map_err(|_| ConfigError::InvalidUrl("json_rpc_endpoint", json_rpc_endpoint))?;
+
+        let sync_interval = interval(
+            "sync_interval",
+            raw.sync_interval.unwrap_or(DOJO_SYNC_INTERVAL),
+        )?;
+        let health_check_interval = interval(
+            "health_check_interval",
+            raw.health_check_interval.unwrap_or(HEALTH_CHECK_INTERVAL),
+        )?;
+
+        let chain_id = raw.chain_id.unwrap_or_else(|| CHAIN_ID.to_string());
+        let chain_id = match chain_id.strip_prefix("0x") {
+            Some(_) => FieldElement::from_hex_be(&chain_id).ok(),
+            None => codec::encode_short(&chain_id).ok(),
+        }
+        .ok_or(ConfigError::InvalidFelt("chain_id", chain_id))?;
+
+        let fee_multiplier = raw.fee_multiplier.unwrap_or(FEE_MULTIPLIER);
+        if !(fee_multiplier.is_finite() && fee_multiplier >= 1.0) {
+            return Err(ConfigError::InvalidNumber(
+                "fee_multiplier",
+                fee_multiplier.to_string(),
+            ));
+        }
+        let fee = FeePolicy {
+            multiplier: fee_multiplier,
+            max_fee: raw.max_fee.map(FieldElement::from),
+            token: parse_felt(
+                "fee_token_address",
+                raw.fee_token_address,
+                FEE_TOKEN_ADDRESS,
+            )?,
+        };
+
+        if raw.rpc_record.is_some() && raw.rpc_replay.is_some() {
+            return Err(ConfigError::Conflict("rpc_record", "rpc_replay"));
+        }
+
+        let (world_address, actions_address) = match &raw.manifest_path {
+            Some(path) => {
+                let manifest = DeploymentManifest::load(path).map_err(ConfigError::Manifest)?;
+                (
+                    check_manifest("world_address", raw.world_address, manifest.world_address)?,
+                    check_manifest(
+                        "actions_address",
+                        raw.actions_address,
+                        manifest.actions_address,
+                    )?,
+                )
+            }
+            None => (
+                parse_felt("world_address", raw.world_address, WORLD_ADDRESS)?,
+                parse_felt("actions_address", raw.actions_address, ACTIONS_ADDRESS)?,
+            ),
+        };
+
+        let mut secret_keys = SecretKeys {
+            allow_plaintext: raw.dev_plaintext_keys.unwrap_or(false),
+            read_only,
+            password: None,
+        };
+
+        let burner = match raw.burner {
+            Some(true) if !secret_keys.allow_plaintext => {
+                return Err(ConfigError::DevOnly("burner"));
+            }
+            // a replay cannot deploy the key generated on its launch, and a recording
+            // would only replay with the burner file it was made with
+            Some(true) if raw.rpc_record.is_some() => {
+                return Err(ConfigError::Conflict("burner", "rpc_record"));
+            }
+            Some(true) if raw.rpc_replay.is_some() => {
+                return Err(ConfigError::Conflict("burner", "rpc_replay"));
+            }
+            Some(true) => Some(BurnerSettings {
+                path: raw.burner_path.unwrap_or_else(|| BURNER_PATH.into()),
+                class_hash: parse_felt(
+                    "burner_class_hash",
+                    raw.burner_class_hash,
+                    BURNER_CLASS_HASH,
+                )?,
+                funding: raw.burner_funding.unwrap_or(BURNER_FUNDING).into(),
+            }),
+            _ => None,
+        };
+
+        let account = AccountKeys {
+            address: parse_felt("account_address", raw.account_address, ACCOUNT_ADDRESS)?,
+            secret_key: secret_keys.resolve(
+                ("account_secret_key", "keystore_path"),
+                raw.account_secret_key,
+                raw.keystore_path,
+            )?,
+        };
+        let mut accounts = vec![account];
+        for raw_account in raw.accounts {
+            let keys = AccountKeys {
+                address: parse_felt("accounts.address", Some(raw_account.address), "")?,
+                secret_key: secret_keys.resolve(
+                    ("accounts.secret_key", "accounts.keystore"),
+                    raw_account.secret_key,
+                    raw_account.keystore,
+                )?,
+            };
+            if !accounts.contains(&keys) {
+                accounts.push(keys);
+            }
+        }
+
+        Ok(Self {
+            json_rpc_endpoint,
+            account_address: account.address,
+            account_secret_key: account.secret_key,
+            world_address,
+            actions_address,
+            sync_interval,
+            chain_id,
+            health_check_interval,
+            rpc_record: raw.rpc_record,
+            rpc_replay: raw.rpc_replay,
+            retry: RetryPolicy {
+                max_retries: raw.retry_attempts.unwrap_or(RETRY_ATTEMPTS),
+                base_delay: Duration::from_millis(
+                    raw.retry_base_delay_ms.unwrap_or(RETRY_BASE_DELAY_MS),
+                ),
+                max_delay: Duration::from_millis(
+                    raw.retry_max_delay_ms.unwrap_or(RETRY_MAX_DELAY_MS),
+                ),
+            },
+            fee,
+            burner,
+            accounts,
+        })
+    }
+
+    /// Plays as `account` instead of the configured account, which is left out of
+    /// `accounts`.
+    pub fn replace_account(&mut self, account: AccountKeys) {
+        let configured = AccountKeys {
+            address: self.account_address,
+            secret_key: self.account_secret_key,
+        };
+        self.accounts
+            .retain(|keys| *keys != configured && *keys != account);
+        self.accounts.insert(0, account);
+
+        self.account_address = account.address;
+        self.account_secret_key = account.secret_key;
+    }
+}
+
+// signing keys come from encrypted keystores, plaintext ones are for local devnets only
+struct SecretKeys {
+    allow_plaintext: bool,
+    // nothing will be signed, keys are not read
+    read_only: bool,
+    // the last one that opened a keystore
+    password: Option<String>,
+}
+
+impl SecretKeys {
+    fn resolve(
+        &mut self,
+        (plaintext_key, keystore_key): (&'static str, &'static str),
+        plaintext: Option<String>,
+        keystore: Option<PathBuf>,
+    ) -> Result<FieldElement, ConfigError> {
+        match (plaintext, keystore) {
+            _ if self.read_only => Ok(FieldElement::ZERO),
+            (Some(_), Some(_)) => Err(ConfigError::Conflict(plaintext_key, keystore_key)),
+            (None, Some(path)) => self.unlock(&path),
+            (Some(value), None) if self.allow_plaintext => {
+                parse_felt(plaintext_key, Some(value), "")
+            }
+            (None, None) if self.allow_plaintext => {
+                Err(ConfigError::NoKey(plaintext_key, keystore_key))
+            }
+            (_, None) => Err(ConfigError::NoKeystore(plaintext_key, keystore_key)),
+        }
+    }
+
+    // the password of the previous keystore is tried first, a keystore it does not open
+    // asks for its own
+    fn unlock(&mut self, path: &Path) -> Result<FieldElement, ConfigError> {
+        if let Some(password) = &self.password {
+            if let Ok(key) = SigningKey::from_keystore(path, password) {
+                return Ok(key.secret_scalar());
+            }
+        }
+
+        let password = self.ask_password(path)?;
+        let key = SigningKey::from_keystore(path, &password)
+            .map_err(|e| ConfigError::Keystore(path.into(), e.to_string()))?;
+        self.password = Some(password);
+        Ok(key.secret_scalar())
+    }
+
+    // `DOJO_KEYSTORE_PASSWORD` for the first keystore, or asked on the terminal
+    fn ask_password(&self, path: &Path) -> Result<String, ConfigError> {
+        match env::var("DOJO_KEYSTORE_PASSWORD") {
+            Ok(password) if self.password.is_none() => Ok(password),
+            _ => rpassword::prompt_password(format!("Password for {}: ", path.display()))
+                .map_err(|e| ConfigError::Io(path.into(), e)),
+        }
+    }
+}
+
+// an address set explicitly next to a manifest has to agree with it
+fn check_manifest(
+    key: &'static str,
+    configured: Option<String>,
+    deployed: FieldElement,
+) -> Result<FieldElement, ConfigError> {
+    match configured {
+        Some(value) => {
+            let configured = FieldElement::from_hex_be(&value)
+                .map_err(|_| ConfigError::InvalidFelt(key, value))?;
+            if configured != deployed {
+                return Err(ConfigError::ManifestMismatch(key, configured, deployed));
+            }
+            Ok(configured)
+        }
+        None => Ok(deployed),
+    }
+}
+
+// seconds between two runs of a periodic task
+fn interval(key: &'static str, seconds: f32) -> Result<f32, ConfigError> {
+    if seconds.is_finite() && seconds > 0.0 {
+        Ok(seconds)
+    } else {
+        Err(ConfigError::InvalidNumber(key, seconds.to_string()))
+    }
+}
+
+fn parse_env<T: FromStr>(name: &'static str) -> Result<Option<T>, ConfigError> {
+    match env::var(name) {
+        Ok(value) if !value.is_empty() => value
+            .parse()
+            .map(Some)
+            .map_err(|_| ConfigError::InvalidNumber(name, value)),
+        _ => Ok(None),
+    }
+}
+
+fn parse_felt(
+    key: &'static str,
+    value: Option<String>,
+    default: &str,
+) -> Result<FieldElement, ConfigError> {
+    let value = value.unwrap_or_else(|| default.to_string());
+    FieldElement::from_hex_be(&value).map_err(|_| ConfigError::InvalidFelt(key, value))
+}
@@ -1,53 +1,677 @@
-use crate::configs;
-use crate::resources::*;
+use crate::backend::{Backend, GameBackend};
+use crate::burner::{self, BurnerSettings};
+use crate::codec;
+use crate::configs::{
+    AccountKeys, DojoConfig, HEALTH_SLOW_RESPONSE, RECEIPT_POLL_INTERVAL, RECEIPT_TIMEOUT,
+    STARTING_TURNS,
+};
+use crate::error::{DojoError, DojoResult};
+use crate::events::{decode_events, ContractEvent};
+use crate::model_cache::{DojoProvider, ModelCache};
+use crate::models::{Door, Game, KeyBuilder, Model, Object};
+use crate::offline::OfflineBackend;
+use crate::resources::{
+    AccountInfo, ActiveAccount, ChainState, DojoConnectionStatus, MovesRemaining, RoomObjects,
+    Spectating,
+};
+use crate::retry::{RetryAttempted, RetryLog};
+use crate::spectator::SpectatorBackend;
+use crate::transactions::{QueuedTransaction, TransactionQueue, TransactionStatus, Transactions};
+use crate::transport::{DojoTransport, TransportError};
 use anyhow::Result;
 use async_compat::Compat;
+use async_trait::async_trait;
 use bevy::{
     prelude::*,
     tasks::{AsyncComputeTaskPool, Task},
 };
-use dojo_types::{primitive::Primitive, schema::Ty};
-use dojo_world::contracts::WorldContractReader;
 use futures_lite::future;
 use regex::Regex;
-use std::thread;
-use std::time::Duration;
-use std::{str::FromStr, sync::Arc};
-use url::Url;
+use std::future::Future;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
 
 use starknet::{
-    accounts::{Account, Call, ExecutionEncoding, SingleOwnerAccount},
+    accounts::{
+        Account, AccountError, AccountFactory, AccountFactoryError, Call, ExecutionEncoding,
+        OpenZeppelinAccountFactory, SingleOwnerAccount,
+    },
     core::{
-        types::{BlockId, BlockTag, FieldElement},
-        utils::{cairo_short_string_to_felt, get_selector_from_name, parse_cairo_short_string},
+        types::{
+            BlockId, BlockTag, Event as EmittedEvent, ExecutionResult, FieldElement,
+            MaybePendingTransactionReceipt, PendingTransactionReceipt, StarknetError,
+            TransactionReceipt,
+        },
+        utils::get_selector_from_name,
+    },
+    providers::{
+        JsonRpcClient, MaybeUnknownErrorCode, Provider, ProviderError, StarknetErrorWithMessage,
     },
-    providers::jsonrpc::HttpTransport,
-    providers::JsonRpcClient,
     signers::{LocalWallet, SigningKey},
 };
 
-#[derive(Resource)]
 pub struct DojoEnv {
     block_id: BlockId,
     world_address: FieldElement,
-    // account to use for performing execution on the world contract
-    account: Arc<SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet>>,
-    provider: JsonRpcClient<HttpTransport>,
+    // account to use for performing execution on the world contract, replaced when
+    // the player switches accounts
+    account: RwLock<Arc<SingleOwnerAccount<DojoProvider, LocalWallet>>>,
+    provider: DojoProvider,
+    transport: DojoTransport,
+    models: ModelCache,
+    queue: TransactionQueue,
+    retries: RetryLog,
+    config: DojoConfig,
 }
 
 impl DojoEnv {
-    fn new(
-        world_address: FieldElement,
-        account: SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet>,
-    ) -> Self {
+    /// Connects to the node in `config` with the configured account, or to the
+    /// recording it replays.
+    pub fn from_config(config: DojoConfig) -> Result<Self, TransportError> {
+        let transport = DojoTransport::from_config(&config)?;
+        Ok(Self::new(config, transport))
+    }
+
+    fn new(config: DojoConfig, transport: DojoTransport) -> Self {
+        let account = AccountKeys {
+            address: config.account_address,
+            secret_key: config.account_secret_key,
+        };
+
         Self {
-            world_address,
-            account: Arc::new(account),
+            world_address: config.world_address,
+            account: RwLock::new(Arc::new(connect_account(&config, &transport, account))),
             block_id: BlockId::Tag(BlockTag::Latest),
-            provider: JsonRpcClient::new(HttpTransport::new(
-                Url::parse(configs::JSON_RPC_ENDPOINT).unwrap(),
-            )),
+            provider: JsonRpcClient::new(transport.clone()),
+            models: ModelCache::new(config.world_address, transport.clone()),
+            transport,
+            queue: TransactionQueue::default(),
+            retries: RetryLog::default(),
+            config,
+        }
+    }
+
+    fn account(&self) -> Arc<SingleOwnerAccount<DojoProvider, LocalWallet>> {
+        self.account.read().unwrap().clone()
+    }
+
+    // sends the call and returns the contract events of its receipt
+    async fn execute(
+        &self,
+        to: FieldElement,
+        entrypoint: &str,
+        calldata: Vec<FieldElement>,
+    ) -> DojoResult<Vec<ContractEvent>> {
+        let selector = get_selector_from_name(entrypoint).map_err(DojoError::encoding)?;
+        let id = self.queue.push(entrypoint);
+
+        // locked across every retry, later actions wait until this one is sent or given
+        // up and go on from the nonce it left
+        let mut nonce = self.queue.nonce().await;
+        let submission = Submission {
+            id,
+            account: self.account(),
+            to,
+            selector,
+            calldata,
+            nonce: Mutex::new(*nonce),
+            unconfirmed: Mutex::new(None),
+        };
+
+        // the receipt poll below already rides out network errors on its own
+        let submitted = self
+            .config
+            .retry
+            .run(entrypoint, &self.retries, || self.submit(&submission))
+            .await;
+        *nonce = *submission.nonce.lock().unwrap();
+        drop(nonce);
+
+        let receipt = match submitted {
+            Ok(transaction_hash) => {
+                self.queue.sent(id, transaction_hash);
+                self.wait_for_receipt(transaction_hash).await
+            }
+            Err(e) => Err(e),
+        };
+
+        self.queue.finish(
+            id,
+            match &receipt {
+                Ok(_) => TransactionStatus::Confirmed,
+                Err(e) => TransactionStatus::Failed(e.to_string()),
+            },
+        );
+        decode_events(self.world_address, receipt_events(&receipt?))
+    }
+
+    // one attempt at sending `submission`, from the nonce it holds
+    async fn submit(&self, submission: &Submission) -> DojoResult<FieldElement> {
+        let mut nonce = *submission.nonce.lock().unwrap();
+        let submitted = self.send(submission, &mut nonce).await;
+        *submission.nonce.lock().unwrap() = nonce;
+        submitted
+    }
+
+    // sends the call with `nonce`, read from the node first when it is `None`. The fee
+    // is estimated first and the call is refused when the account cannot pay it.
+    //
+    // A send that failed in transport may still have reached the node, it is kept in
+    // `unconfirmed` and sent again unchanged. The node then refuses the copy if the
+    // first one got through, instead of running the action twice.
+    async fn send(
+        &self,
+        submission: &Submission,
+        nonce: &mut Option<FieldElement>,
+    ) -> DojoResult<FieldElement> {
+        let account = &submission.account;
+        let mut resynced = false;
+
+        loop {
+            let resend = *submission.unconfirmed.lock().unwrap();
+            let (current, max_fee) = match resend {
+                Some(sent) => (sent.nonce, sent.max_fee),
+                None => {
+                    let current = match *nonce {
+                        Some(current) => current,
+                        None => self
+                            .provider
+                            .get_nonce(BlockId::Tag(BlockTag::Pending), account.address())
+                            .await
+                            .map_err(DojoError::network)?,
+                    };
+
+                    let estimate = account
+                        .execute(submission.calls())
+                        .nonce(current)
+                        .estimate_fee()
+                        .await;
+
+                    // nothing is sent when the estimate fails or the fee is refused, the
+                    // nonce stays valid
+                    let estimate = match estimate {
+                        Ok(estimate) => estimate,
+                        Err(e) => match rejection(&e) {
+                            // another client used the account, read the nonce again once
+                            Rejection::StaleNonce if !resynced => {
+                                *nonce = None;
+                                resynced = true;
+                                continue;
+                            }
+                            kind => return Err(kind.error(e)),
+                        },
+                    };
+                    let quote = self.config.fee.quote(estimate.overall_fee)?;
+                    self.queue.quoted(submission.id, quote);
+                    self.config
+                        .fee
+                        .check_balance(&self.provider, account.address(), &quote)
+                        .await?;
+
+                    (current, quote.max_fee)
+                }
+            };
+
+            let prepared = account
+                .execute(submission.calls())
+                .nonce(current)
+                .max_fee(max_fee)
+                .prepared()
+                .map_err(DojoError::encoding)?;
+            let transaction_hash = prepared.transaction_hash();
+
+            match prepared.send().await {
+                Ok(result) => {
+                    *nonce = Some(current + FieldElement::ONE);
+                    return Ok(result.transaction_hash);
+                }
+                Err(e) => match (rejection(&e), resend) {
+                    // the earlier send got through, its receipt is awaited instead
+                    (Rejection::Duplicate, Some(sent)) => {
+                        *nonce = Some(current + FieldElement::ONE);
+                        return Ok(sent.transaction_hash);
+                    }
+                    // its nonce was used, by the earlier send only if the node has it
+                    (Rejection::StaleNonce, Some(sent)) => {
+                        if self.is_received(sent.transaction_hash).await? {
+                            *nonce = Some(current + FieldElement::ONE);
+                            return Ok(sent.transaction_hash);
+                        }
+                        // another transaction took the nonce, the call is signed again
+                        *submission.unconfirmed.lock().unwrap() = None;
+                        *nonce = None;
+                        resynced = true;
+                    }
+                    (Rejection::StaleNonce, None) if !resynced => {
+                        *nonce = None;
+                        resynced = true;
+                    }
+                    // the nonce is kept for the copy, and for the next action if the
+                    // retries give up: a used nonce is then read again like a stale one
+                    (Rejection::Transport, _) => {
+                        *submission.unconfirmed.lock().unwrap() = Some(UnconfirmedSend {
+                            nonce: current,
+                            max_fee,
+                            transaction_hash,
+                        });
+                        return Err(DojoError::network(e));
+                    }
+                    (kind, _) => {
+                        *nonce = None;
+                        return Err(kind.error(e));
+                    }
+                },
+            }
+        }
+    }
+
+    // keys of the models owned by the account signing the transactions
+    fn keys(&self) -> KeyBuilder {
+        KeyBuilder::new(self.account().address())
+    }
+
+    async fn read<M: Model>(&self, keys: KeyBuilder) -> DojoResult<M> {
+        self.models
+            .read(&keys.build::<M>()?, &self.config.retry, &self.retries)
+            .await
+    }
+
+    /// Deploys an OpenZeppelin account of class `class_hash` signed by `secret_key`,
+    /// after topping its balance up to `funding` from the configured account to pay for
+    /// the deployment.
+    /// An account already deployed is left as it is. Returns its address.
+    pub async fn deploy_account(
+        &self,
+        class_hash: FieldElement,
+        secret_key: FieldElement,
+        funding: FieldElement,
+    ) -> DojoResult<FieldElement> {
+        let signer = LocalWallet::from_signing_key(SigningKey::from_secret_scalar(secret_key));
+        let factory = OpenZeppelinAccountFactory::new(
+            class_hash,
+            self.config.chain_id,
+            signer,
+            JsonRpcClient::new(self.transport.clone()),
+        )
+        .await
+        .map_err(DojoError::encoding)?;
+
+        let deployment = factory.deploy(FieldElement::ZERO);
+        let address = deployment.address();
+        if self.is_deployed(address).await? {
+            return Ok(address);
+        }
+
+        // topped up to `funding` only, a deployment that failed after its transfer does
+        // not fund the burner again on the next launch
+        let balance = self.config.fee.balance(&self.provider, address).await?;
+        if balance < funding {
+            // an ERC-20 `transfer`, the amount is a u256
+            self.execute(
+                self.config.fee.token,
+                "transfer",
+                vec![address, funding - balance, FieldElement::ZERO],
+            )
+            .await?;
+        }
+
+        let estimate = deployment
+            .estimate_fee()
+            .await
+            .map_err(|e| deploy_rejection(&e).error(e))?;
+        let quote = self.config.fee.quote(estimate.overall_fee)?;
+        let result = deployment
+            .max_fee(quote.max_fee)
+            .send()
+            .await
+            .map_err(|e| deploy_rejection(&e).error(e))?;
+        self.wait_for_receipt(result.transaction_hash).await?;
+
+        Ok(address)
+    }
+
+    async fn is_deployed(&self, address: FieldElement) -> DojoResult<bool> {
+        match self
+            .provider
+            .get_class_hash_at(BlockId::Tag(BlockTag::Pending), address)
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(e) if starknet_code(&e) == Some(&StarknetError::ContractNotFound) => Ok(false),
+            Err(e) => Err(DojoError::network(e)),
+        }
+    }
+
+    // whether the node has a receipt for `transaction_hash`, accepted or reverted
+    async fn is_received(&self, transaction_hash: FieldElement) -> DojoResult<bool> {
+        match self
+            .provider
+            .get_transaction_receipt(transaction_hash)
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(e) if starknet_code(&e) == Some(&StarknetError::TransactionHashNotFound) => {
+                Ok(false)
+            }
+            Err(e) => Err(DojoError::network(e)),
+        }
+    }
+
+    // polls the receipt until the transaction is accepted or reverted
+    async fn wait_for_receipt(
+        &self,
+        transaction_hash: FieldElement,
+    ) -> DojoResult<MaybePendingTransactionReceipt> {
+        let started = Instant::now();
+
+        loop {
+            // an error usually means the node does not know the transaction yet
+            let status = match self
+                .provider
+                .get_transaction_receipt(transaction_hash)
+                .await
+            {
+                Ok(receipt) => match execution_result(&receipt) {
+                    Some(ExecutionResult::Reverted { reason }) => {
+                        return Err(DojoError::revert(format!(
+                            "transaction {:#x} reverted: {}",
+                            transaction_hash, reason
+                        )));
+                    }
+                    Some(ExecutionResult::Succeeded) => {
+                        if let MaybePendingTransactionReceipt::Receipt(_) = receipt {
+                            return Ok(receipt);
+                        }
+                        String::from("pending")
+                    }
+                    None => {
+                        return Err(DojoError::decode(format!(
+                            "transaction {:#x} is neither an invoke nor an account deployment",
+                            transaction_hash
+                        )));
+                    }
+                },
+                Err(e) => e.to_string(),
+            };
+
+            if started.elapsed() >= RECEIPT_TIMEOUT {
+                return Err(DojoError::network(format!(
+                    "no receipt for transaction {:#x} after {:?} ({})",
+                    transaction_hash, RECEIPT_TIMEOUT, status
+                )));
+            }
+            tokio::time::sleep(RECEIPT_POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// Chain id and latest block of the node behind `provider`. A healthy node is also
+/// asked whether the world was upgraded, see [`ModelCache::check_upgrade`].
+pub(crate) async fn node_health(
+    provider: &DojoProvider,
+    models: &ModelCache,
+    chain_id: FieldElement,
+) -> DojoConnectionStatus {
+    let started = Instant::now();
+
+    let actual = match provider.chain_id().await {
+        Ok(chain_id) => chain_id,
+        Err(e) => {
+            return DojoConnectionStatus::Disconnected {
+                reason: e.to_string(),
+            }
+        }
+    };
+    if actual != chain_id {
+        return DojoConnectionStatus::WrongChain {
+            expected: chain_id,
+            actual,
+        };
+    }
+
+    match provider.block_number().await {
+        Err(e) => DojoConnectionStatus::Degraded {
+            reason: format!("cannot read the latest block: {e}"),
+        },
+        Ok(_) if started.elapsed() > HEALTH_SLOW_RESPONSE => DojoConnectionStatus::Degraded {
+            reason: format!("the node took {:?} to answer", started.elapsed()),
+        },
+        Ok(block_number) => {
+            // piggybacks on the health interval, a failed check is tried again next time
+            if let Err(e) = models.check_upgrade(provider).await {
+                println!("Cannot check the world for upgrades: {e}");
+            }
+            DojoConnectionStatus::Connected { block_number }
+        }
+    }
+}
+
+// an action's call and what it keeps across the retries of its submission
+struct Submission {
+    id: u64,
+    // every retry signs with the account the action was taken with
+    account: Arc<SingleOwnerAccount<DojoProvider, LocalWallet>>,
+    to: FieldElement,
+    selector: FieldElement,
+    calldata: Vec<FieldElement>,
+    // the account's next nonce, locked in the queue until the submission is over
+    nonce: Mutex<Option<FieldElement>>,
+    unconfirmed: Mutex<Option<UnconfirmedSend>>,
+}
+
+impl Submission {
+    fn calls(&self) -> Vec<Call> {
+        vec![Call {
+            to: self.to,
+            selector: self.selector,
+            calldata: self.calldata.clone(),
+        }]
+    }
+}
+
+// a signed transaction whose send failed in transport, it may have reached the node
+#[derive(Clone, Copy)]
+struct UnconfirmedSend {
+    nonce: FieldElement,
+    max_fee: FieldElement,
+    transaction_hash: FieldElement,
+}
+
+// why the node did not take a transaction
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Rejection {
+    // the nonce was already used
+    StaleNonce,
+    // the same transaction was already received
+    Duplicate,
+    // the node could not be reached, was rate limited or failed on its own
+    Transport,
+    // the node ran the transaction and refused it, or it could not be built
+    Refused,
+}
+
+impl Rejection {
+    fn of<E>(e: &ProviderError<E>) -> Self {
+        match starknet_code(e) {
+            Some(StarknetError::InvalidTransactionNonce) => Rejection::StaleNonce,
+            Some(StarknetError::DuplicateTx) => Rejection::Duplicate,
+            Some(StarknetError::FailedToReceiveTransaction | StarknetError::UnexpectedError) => {
+                Rejection::Transport
+            }
+            Some(_) => Rejection::Refused,
+            // rate limits, internal node errors and anything the spec does not define
+            None => Rejection::Transport,
+        }
+    }
+
+    fn error(self, e: impl std::fmt::Display) -> DojoError {
+        match self {
+            Rejection::Transport => DojoError::network(e),
+            _ => DojoError::revert(e),
+        }
+    }
+}
+
+fn rejection<S, P>(e: &AccountError<S, P>) -> Rejection {
+    match e {
+        AccountError::Provider(e) => Rejection::of(e),
+        _ => Rejection::Refused,
+    }
+}
+
+fn deploy_rejection<S, P>(e: &AccountFactoryError<S, P>) -> Rejection {
+    match e {
+        AccountFactoryError::Provider(e) => Rejection::of(e),
+        _ => Rejection::Refused,
+    }
+}
+
+// the Starknet error the node answered with, `None` for transport and JSON-RPC failures
+fn starknet_code<E>(e: &ProviderError<E>) -> Option<&StarknetError> {
+    match e {
+        ProviderError::StarknetError(StarknetErrorWithMessage {
+            code: MaybeUnknownErrorCode::Known(code),
+            ..
+        }) => Some(code),
+        _ => None,
+    }
+}
+
+fn connect_account(
+    config: &DojoConfig,
+    transport: &DojoTransport,
+    keys: AccountKeys,
+) -> SingleOwnerAccount<DojoProvider, LocalWallet> {
+    SingleOwnerAccount::new(
+        JsonRpcClient::new(transport.clone()),
+        LocalWallet::from_signing_key(SigningKey::from_secret_scalar(keys.secret_key)),
+        keys.address,
+        config.chain_id,
+        ExecutionEncoding::Legacy,
+    )
+}
+
+fn execution_result(receipt: &MaybePendingTransactionReceipt) -> Option<&ExecutionResult> {
+    match receipt {
+        MaybePendingTransactionReceipt::Receipt(TransactionReceipt::Invoke(receipt)) => {
+            Some(&receipt.execution_result)
+        }
+        MaybePendingTransactionReceipt::Receipt(TransactionReceipt::DeployAccount(receipt)) => {
+            Some(&receipt.execution_result)
+        }
+        MaybePendingTransactionReceipt::PendingReceipt(PendingTransactionReceipt::Invoke(
+            receipt,
+        )) => Some(&receipt.execution_result),
+        MaybePendingTransactionReceipt::PendingReceipt(
+            PendingTransactionReceipt::DeployAccount(receipt),
+        ) => Some(&receipt.execution_result),
+        _ => None,
+    }
+}
+
+fn receipt_events(receipt: &MaybePendingTransactionReceipt) -> &[EmittedEvent] {
+    match receipt {
+        MaybePendingTransactionReceipt::Receipt(TransactionReceipt::Invoke(receipt)) => {
+            &receipt.events
         }
+        MaybePendingTransactionReceipt::PendingReceipt(PendingTransactionReceipt::Invoke(
+            receipt,
+        )) => &receipt.events,
+        _ => &[],
+    }
+}
+
+#[async_trait]
+impl GameBackend for DojoEnv {
+    async fn initialise(&self, turns_remaining: u64) -> DojoResult<Vec<ContractEvent>> {
+        self.execute(
+            self.config.actions_address,
+            "initialise",
+            vec![turns_remaining.into()],
+        )
+        .await
+    }
+
+    async fn spawn_object(
+        &self,
+        objects_id: Vec<FieldElement>,
+        objects_description: Vec<String>,
+    ) -> DojoResult<Vec<ContractEvent>> {
+        // every description as an array of short strings, one after the other
+        let descriptions: Vec<FieldElement> = objects_description
+            .iter()
+            .flat_map(|description| codec::encode_felt_array(description))
+            .collect();
+
+        let mut calldata = Vec::new();
+
+        // Add the length of each vector as the first element in calldata
+        calldata.push(objects_id.len().into());
+        calldata.extend(objects_id.iter().cloned());
+        calldata.push(descriptions.len().into());
+        calldata.extend(descriptions);
+
+        self.execute(self.config.actions_address, "spawn_object", calldata)
+            .await
+    }
+
+    async fn interact(&self, object_id: FieldElement) -> DojoResult<Vec<ContractEvent>> {
+        self.execute(self.config.actions_address, "interact", vec![object_id])
+            .await
+    }
+
+    async fn escape(&self, secret: FieldElement) -> DojoResult<Vec<ContractEvent>> {
+        self.execute(self.config.actions_address, "escape", vec![secret])
+            .await
+    }
+
+    async fn switch_account(&self, keys: AccountKeys) -> DojoResult<()> {
+        // submissions of the previous account go out first, its nonce is of no use after
+        let mut nonce = self.queue.nonce().await;
+        *self.account.write().unwrap() =
+            Arc::new(connect_account(&self.config, &self.transport, keys));
+        *nonce = None;
+        Ok(())
+    }
+
+    async fn health(&self) -> DojoConnectionStatus {
+        node_health(&self.provider, &self.models, self.config.chain_id).await
+    }
+
+    async fn account_info(&self) -> DojoResult<AccountInfo> {
+        let address = self.account().address();
+        let nonce = self
+            .provider
+            .get_nonce(BlockId::Tag(BlockTag::Pending), address)
+            .await
+            .map_err(DojoError::network)?;
+        let balance = self.config.fee.balance(&self.provider, address).await?;
+
+        Ok(AccountInfo {
+            address,
+            nonce,
+            balance: Some(balance),
+        })
+    }
+
+    fn transactions(&self) -> Vec<QueuedTransaction> {
+        self.queue.snapshot()
+    }
+
+    fn take_retries(&self) -> Vec<RetryAttempted> {
+        self.retries.drain()
+    }
+
+    async fn game(&self) -> DojoResult<Game> {
+        self.read(self.keys()).await
+    }
+
+    async fn object(&self, object_id: FieldElement) -> DojoResult<Object> {
+        self.read(self.keys().object_id(object_id)).await
+    }
+
+    async fn door(&self, game_id: u32) -> DojoResult<Door> {
+        self.read(self.keys().game_id(game_id)).await
     }
 }
 
@@ -55,42 +679,121 @@ pub struct DojoPlugin;
 
 impl Plugin for DojoPlugin {
     fn build(&self, app: &mut App) {
-        let url = Url::parse(configs::JSON_RPC_ENDPOINT).unwrap();
-        let account_address = FieldElement::from_str(configs::ACCOUNT_ADDRESS).unwrap();
-
-        let account = SingleOwnerAccount::new(
-            JsonRpcClient::new(HttpTransport::new(url)),
-            LocalWallet::from_signing_key(SigningKey::from_secret_scalar(
-                FieldElement::from_str(configs::ACCOUNT_SECRET_KEY).unwrap(),
-            )),
-            account_address,
-            cairo_short_string_to_felt("KATANA").unwrap(),
-            ExecutionEncoding::Legacy,
-        );
+        let config = app
+            .world
+            .get_resource::<DojoConfig>()
+            .expect("DojoConfig must be inserted before DojoPlugin")
+            .clone();
 
-        let world_address = FieldElement::from_str(configs::WORLD_ADDRESS).unwrap();
+        let spectating = app.world.get_resource::<Spectating>().cloned();
+
+        let backend: Arc<dyn GameBackend> = if let Some(spectating) = &spectating {
+            println!("Spectating {:#x}, actions are disabled.", spectating.player);
+            match SpectatorBackend::from_config(config, spectating.player) {
+                Ok(spectator) => Arc::new(spectator),
+                Err(e) => {
+                    eprintln!("Cannot set up the Dojo transport: {e}");
+                    std::process::exit(1);
+                }
+            }
+        } else if offline_mode() {
+            println!("Running offline, chain calls are simulated in memory.");
+            Arc::new(OfflineBackend::new(config.account_address))
+        } else {
+            let burner = config.burner.clone();
+            match DojoEnv::from_config(config) {
+                Ok(env) => {
+                    let env = Arc::new(env);
+                    // the configured account funds the burner, which is played from then on
+                    if let Some(settings) = burner {
+                        app.insert_resource(BurnerSetup {
+                            env: env.clone(),
+                            settings,
+                        });
+                    }
+                    env
+                }
+                Err(e) => {
+                    eprintln!("Cannot set up the Dojo transport: {e}");
+                    std::process::exit(1);
+                }
+            }
+        };
 
         // creating world and adding systems
         app
             // resources
-            .insert_resource(DojoEnv::new(world_address, account))
+            .insert_resource(Backend(backend))
+            .init_resource::<ChainState>()
+            .init_resource::<RoomObjects>()
+            .init_resource::<Transactions>()
+            .init_resource::<DojoConnectionStatus>()
+            .init_resource::<ActiveAccount>()
+            // events
+            .add_event::<GameInitialized>()
+            .add_event::<ObjectsSpawned>()
+            .add_event::<ObjectInspected>()
+            .add_event::<EscapeAttempted>()
+            .add_event::<StateSynced>()
+            .add_event::<DojoError>()
+            .add_event::<RetryAttempted>()
+            .add_event::<ContractEvent>()
+            .add_event::<HealthChecked>()
+            .add_event::<AccountSwitched>()
+            .add_event::<BurnerStarted>()
+            .add_event::<AccountInfoRead>()
             // starting system
-            .add_systems(Startup, (setup, task_init, handle_task_spawn_object))
+            .add_systems(
+                Startup,
+                (
+                    setup,
+                    task_init.run_if(
+                        not(resource_exists::<Spectating>())
+                            .and_then(not(resource_exists::<BurnerSetup>())),
+                    ),
+                    task_start_burner.run_if(resource_exists::<BurnerSetup>()),
+                ),
+            )
             // update systems
             .add_systems(
                 Update,
                 (
                     sync_dojo_state,
-                    handle_task_init,
-                    handle_task_interact,
-                    handle_task_escape,
+                    apply_synced_state,
+                    check_health,
+                    apply_health,
+                    handle_dojo_task::<HealthChecked>,
+                    update_transactions,
+                    refresh_account_info,
+                    apply_account_info,
+                    handle_dojo_task::<AccountInfoRead>,
+                    forward_retries,
+                    forward_contract_events,
+                    handle_dojo_task::<StateSynced>,
+                    handle_dojo_task::<GameInitialized>,
+                    handle_dojo_task::<ObjectsSpawned>,
+                    handle_dojo_task::<ObjectInspected>,
+                    handle_dojo_task::<EscapeAttempted>,
+                    handle_dojo_task::<AccountSwitched>,
+                    on_account_switched,
+                    handle_dojo_task::<BurnerStarted>,
+                    on_burner_started,
                 ),
             );
     }
 }
 
-fn setup(mut commands: Commands) {
-    commands.spawn(DojoSyncTime::from_seconds(configs::DOJO_SYNC_INTERVAL));
+// `--offline` on the command line or the `offline` cargo feature
+fn offline_mode() -> bool {
+    cfg!(feature = "offline") || std::env::args().any(|arg| arg == "--offline")
+}
+
+fn setup(mut commands: Commands, config: Res<DojoConfig>) {
+    commands.spawn(DojoSyncTime::from_seconds(config.sync_interval));
+    commands.spawn(DojoHealthTime(Timer::from_seconds(
+        config.health_check_interval,
+        TimerMode::Repeating,
+    )));
 }
 
 #[derive(Component)]
@@ -106,322 +809,412 @@ impl DojoSyncTime {
     }
 }
 
-fn sync_dojo_state(mut dojo_sync_time: Query<&mut DojoSyncTime>, time: Res<Time>) {
-    let mut dojo_time = dojo_sync_time.single_mut();
+#[derive(Component)]
+struct DojoHealthTime(Timer);
 
-    if dojo_time.timer.just_finished() {
-        dojo_time.timer.reset();
-    } else {
-        dojo_time.timer.tick(time.delta());
+#[derive(Event)]
+struct HealthChecked(DojoConnectionStatus);
+
+fn check_health(
+    mut commands: Commands,
+    mut health_time: Query<&mut DojoHealthTime>,
+    time: Res<Time>,
+    backend: Res<Backend>,
+    status: Res<DojoConnectionStatus>,
+    in_flight: Query<(), With<DojoTask<HealthChecked>>>,
+) {
+    let mut health_time = health_time.single_mut();
+
+    // the first check runs right away
+    let due = health_time.0.tick(time.delta()).just_finished()
+        || *status == DojoConnectionStatus::Connecting;
+    if !due || !in_flight.is_empty() {
+        return;
     }
+
+    let backend = backend.clone();
+    spawn_dojo_task(&mut commands, async move {
+        Ok(HealthChecked(backend.health().await))
+    });
 }
 
-#[derive(Component)]
-struct InitializeGame(Task<bool>);
+fn apply_health(mut events: EventReader<HealthChecked>, mut status: ResMut<DojoConnectionStatus>) {
+    for HealthChecked(checked) in events.read() {
+        if *status != *checked {
+            println!("Dojo connection: {:?}", checked);
+            *status = checked.clone();
+        }
+    }
+}
 
-fn task_init(mut commands: Commands, env: Res<DojoEnv>) {
-    let account = env.account.clone();
-    let thread_pool = AsyncComputeTaskPool::get();
+#[derive(Event)]
+struct AccountInfoRead(AccountInfo);
 
-    let task = thread_pool.spawn(Compat::new(async move {
-        let turns_remaining: u64 = 10;
-        match account
-            .execute(vec![Call {
-                to: FieldElement::from_hex_be(configs::ACTIONS_ADDRESS).unwrap(),
-                selector: get_selector_from_name("initialise").unwrap(),
-                calldata: vec![turns_remaining.into()],
-            }])
-            .send()
-            .await
-        {
-            Ok(_) => {
-                println!("Game Initialized.");
-                true
-            }
-            Err(e) => {
-                println!("Error {}", e);
-                false
-            }
-        }
-    }));
-    commands.spawn(InitializeGame(task));
+// read on startup, after an account switch and after every confirmed transaction
+fn refresh_account_info(
+    mut commands: Commands,
+    backend: Res<Backend>,
+    active_account: Res<ActiveAccount>,
+    transactions: Res<Transactions>,
+    mut last_confirmed: Local<Option<u64>>,
+    mut stale: Local<bool>,
+    in_flight: Query<(), With<DojoTask<AccountInfoRead>>>,
+) {
+    let confirmed = transactions.latest_confirmed();
+    if active_account.is_changed() || confirmed != *last_confirmed {
+        *last_confirmed = confirmed;
+        *stale = true;
+    }
+    // a read already in flight may predate the change, read again once it is done
+    if !*stale || !in_flight.is_empty() {
+        return;
+    }
+    *stale = false;
+
+    let backend = backend.clone();
+    spawn_dojo_task(&mut commands, async move {
+        Ok(AccountInfoRead(backend.account_info().await?))
+    });
 }
 
-fn handle_task_init(mut commands: Commands, mut game_task: Query<(Entity, &mut InitializeGame)>) {
-    for (entity, mut task) in &mut game_task {
-        if let Some(_) = future::block_on(future::poll_once(&mut task.0)) {
-            commands.entity(entity).remove::<InitializeGame>();
-        }
+fn apply_account_info(mut commands: Commands, mut events: EventReader<AccountInfoRead>) {
+    for AccountInfoRead(info) in events.read() {
+        commands.insert_resource(info.clone());
     }
 }
-enum ObjectData {
-    TurnsRemaining(u64),
-    ObjectDescription(String),
-    IsFinished(bool),
+
+/// Models read back by the periodic sync, including changes made by other clients.
+#[derive(Event)]
+pub struct StateSynced {
+    // account the models were read for, results of an account played before are dropped
+    pub player: FieldElement,
+    pub game: Game,
+    pub objects: Vec<Object>,
+    // objects inspected since the last sync by the player a spectator follows
+    pub inspected: Vec<FieldElement>,
 }
 
-#[derive(Component)]
-struct InteractObject(Task<Vec<ObjectData>>);
+fn sync_dojo_state(
+    mut commands: Commands,
+    mut dojo_sync_time: Query<&mut DojoSyncTime>,
+    time: Res<Time>,
+    backend: Res<Backend>,
+    room_objects: Res<RoomObjects>,
+    in_flight: Query<(), With<DojoTask<StateSynced>>>,
+) {
+    let mut dojo_time = dojo_sync_time.single_mut();
 
-pub fn task_interact(commands: &mut Commands, env: &Res<DojoEnv>, object_id: FieldElement) {
-    let account = env.account.clone();
-    let thread_pool = AsyncComputeTaskPool::get();
-    let world_address = env.world_address.clone();
-
-    let task = thread_pool.spawn(Compat::new(async move {
-        let mut my_list: Vec<ObjectData> = Vec::new();
-        match account
-            .execute(vec![Call {
-                to: FieldElement::from_hex_be(configs::ACTIONS_ADDRESS).unwrap(),
-                selector: get_selector_from_name("interact").unwrap(),
-                calldata: vec![object_id],
-            }])
-            .send()
-            .await
-        {
-            Ok(_) => {
-                thread::sleep(Duration::from_millis(250));
-                let schema = fetch_schema(world_address, object_id, String::from("Object")).await;
-
-                if let Ty::Struct(struct_ty) = schema {
-                    for child in struct_ty.children {
-                        if child.name == "description" {
-                            // println!("{} - {}", data, child.name);
-                            if let Ty::Primitive(Primitive::Felt252(Some(felt))) = child.ty {
-                                my_list
-                                    .push(ObjectData::ObjectDescription(FieldElement::to_hex(felt)))
-                            }
-                        }
-                    }
-                }
+    if !dojo_time.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    // a slow node should not pile up reads
+    if !in_flight.is_empty() {
+        return;
+    }
 
-                let schema = fetch_schema(world_address, object_id, String::from("Game")).await;
-                if let Ty::Struct(struct_ty) = schema {
-                    for child in struct_ty.children {
-                        if child.name == "turns_remaining" {
-                            // println!("{}", child.name);
-                            if let Ty::Primitive(Primitive::U64(Some(felt))) = child.ty {
-                                // println!("{}", felt);
-                                // println!("-----------");
-                                my_list.push(ObjectData::TurnsRemaining(felt));
-                            }
-                        }
-                        if child.name == "is_finished" {
-                            if let Ty::Primitive(Primitive::Bool(Some(felt))) = child.ty {
-                                my_list.push(ObjectData::IsFinished(felt));
-                            }
-                        }
-                    }
-                }
-                my_list
-            }
-            Err(e) => {
-                println!("Error {}", e);
-                let my_list: Vec<ObjectData> = Vec::new();
-                my_list
-            }
+    let backend = backend.clone();
+    let object_ids = room_objects.ids.clone();
+
+    spawn_dojo_task(&mut commands, async move {
+        let game = backend.game().await?;
+        let mut objects = Vec::with_capacity(object_ids.len());
+        for object_id in object_ids {
+            objects.push(backend.object(object_id).await?);
         }
-    }));
-    commands.spawn(InteractObject(task));
+        let inspected = backend.inspected_objects().await?;
+        Ok(StateSynced {
+            player: game.player,
+            game,
+            objects,
+            inspected,
+        })
+    });
 }
 
-fn handle_task_interact(
-    mut commands: Commands,
-    mut game_task: Query<(Entity, &mut InteractObject)>,
+fn apply_synced_state(
+    mut events: EventReader<StateSynced>,
+    mut chain_state: ResMut<ChainState>,
     mut moves: ResMut<MovesRemaining>,
+    mut spectating: Option<ResMut<Spectating>>,
+    config: Res<DojoConfig>,
+    active_account: Res<ActiveAccount>,
 ) {
-    for (entity, mut task) in &mut game_task {
-        if let Some(response) = future::block_on(future::poll_once(&mut task.0)) {
-            for obj in response {
-                match obj {
-                    ObjectData::TurnsRemaining(turns) => {
-                        println!("Turns remaining: {}", turns);
-                        moves.0 = turns;
-                    }
-                    ObjectData::ObjectDescription(text) => {
-                        let felt = parse_felt_value(&text).unwrap();
-                        let decoded = parse_cairo_short_string(&felt).unwrap();
-                        println!("Object description: {decoded}");
-                    }
-                    ObjectData::IsFinished(_) => {}
-                }
+    let player = match &spectating {
+        Some(spectating) => Some(spectating.player),
+        None => config
+            .accounts
+            .get(active_account.0)
+            .map(|keys| keys.address),
+    };
+
+    // a sync started before an account switch reads the previous account's models
+    for event in events.read().filter(|event| Some(event.player) == player) {
+        if event.game.is_initialised() && moves.0 != event.game.turns_remaining {
+            moves.0 = event.game.turns_remaining;
+        }
+
+        if let Some(spectating) = spectating.as_mut() {
+            // a new game starts with nothing inspected
+            let previous = chain_state.game.map(|game| game.game_id);
+            if previous.is_some_and(|game_id| game_id != event.game.game_id) {
+                spectating.inspected.clear();
             }
+            spectating.inspected.extend(event.inspected.iter().copied());
+        }
 
-            commands.entity(entity).remove::<InteractObject>();
+        chain_state.game = Some(event.game);
+        for object in event
+            .objects
+            .iter()
+            .filter(|object| object.player == event.player)
+        {
+            chain_state.objects.insert(object.object_id, *object);
         }
     }
 }
 
+fn update_transactions(backend: Res<Backend>, mut transactions: ResMut<Transactions>) {
+    transactions.0 = backend.transactions();
+}
+
+fn forward_retries(backend: Res<Backend>, mut events: EventWriter<RetryAttempted>) {
+    events.send_batch(backend.take_retries());
+}
+
+/// Background call against the backend. Once it completes its output is sent as a `T`
+/// event, or as a [`DojoError`] event when it failed.
 #[derive(Component)]
-struct EscapeGame(Task<Vec<ObjectData>>);
+pub struct DojoTask<T: Event>(Task<DojoResult<T>>);
 
-pub fn task_escape(commands: &mut Commands, env: &Res<DojoEnv>, secret: String) {
-    let account = env.account.clone();
+fn spawn_dojo_task<T: Event>(
+    commands: &mut Commands,
+    future: impl Future<Output = DojoResult<T>> + Send + 'static,
+) {
     let thread_pool = AsyncComputeTaskPool::get();
-    let world_address = env.world_address.clone();
-
-    let task = thread_pool.spawn(Compat::new(async move {
-        let mut my_list: Vec<ObjectData> = Vec::new();
-        match account
-            .execute(vec![Call {
-                to: FieldElement::from_hex_be(configs::ACTIONS_ADDRESS).unwrap(),
-                selector: get_selector_from_name("escape").unwrap(),
-                calldata: vec![cairo_short_string_to_felt(&secret).unwrap()],
-            }])
-            .send()
-            .await
-        {
-            Ok(_) => {
-                thread::sleep(Duration::from_millis(250));
-                let schema = fetch_schema(
-                    world_address,
-                    cairo_short_string_to_felt("Door").unwrap(),
-                    String::from("Game"),
-                )
-                .await;
-
-                if let Ty::Struct(struct_ty) = schema {
-                    for child in struct_ty.children {
-                        if child.name == "turns_remaining" {
-                            if let Ty::Primitive(Primitive::U64(Some(felt))) = child.ty {
-                                my_list.push(ObjectData::TurnsRemaining(felt));
-                            }
-                        } else if child.name == "is_finished" {
-                            if let Ty::Primitive(Primitive::Bool(Some(felt))) = child.ty {
-                                my_list.push(ObjectData::IsFinished(felt));
-                            }
-                        }
-                    }
-                }
-                my_list
-            }
-            Err(e) => {
-                println!("Error {}", e);
-                let my_list: Vec<ObjectData> = Vec::new();
-                my_list
-            }
-        }
-    }));
-    commands.spawn(EscapeGame(task));
+    let task = thread_pool.spawn(Compat::new(future));
+    commands.spawn(DojoTask(task));
 }
 
-fn handle_task_escape(
+fn handle_dojo_task<T: Event>(
     mut commands: Commands,
-    mut game_task: Query<(Entity, &mut EscapeGame)>,
-    mut moves: ResMut<MovesRemaining>,
+    mut tasks: Query<(Entity, &mut DojoTask<T>)>,
+    mut events: EventWriter<T>,
+    mut errors: EventWriter<DojoError>,
 ) {
-    for (entity, mut task) in &mut game_task {
+    for (entity, mut task) in &mut tasks {
         if let Some(response) = future::block_on(future::poll_once(&mut task.0)) {
-            for obj in response {
-                match obj {
-                    ObjectData::ObjectDescription(_) => {}
-                    ObjectData::TurnsRemaining(turns) => {
-                        println!("Turns remaining: {}", turns);
-                        moves.0 = turns;
-                    }
-                    ObjectData::IsFinished(value) => {
-                        println!("{}", value);
-                        if value {
-                            println!("You have escaped the room!");
-                        } else {
-                            println!("Wrong secret. Try again.")
-                        }
-                    }
-                }
+            match response {
+                Ok(event) => events.send(event),
+                Err(e) => errors.send(e),
             }
-            commands.entity(entity).remove::<EscapeGame>();
+            commands.entity(entity).despawn();
         }
     }
 }
 
-#[derive(Component)]
-struct TaskSpawnObject(Task<bool>);
+#[derive(Event, Clone)]
+pub struct GameInitialized {
+    pub game: Game,
+    // an unfinished game was found on-chain, its objects are already spawned
+    pub resumed: bool,
+    // emitted by `initialise`, empty for a resumed game
+    pub events: Vec<ContractEvent>,
+}
 
-pub fn task_spawn_object(
-    commands: &mut Commands,
-    env: &Res<DojoEnv>,
-    objects_id: Vec<FieldElement>,
-    objects_description: Vec<FieldElement>,
-) {
-    let account = env.account.clone();
-    let thread_pool = AsyncComputeTaskPool::get();
+#[derive(Event)]
+pub struct ObjectsSpawned {
+    pub count: usize,
+}
 
-    let task = thread_pool.spawn(Compat::new(async move {
-        let mut calldata = Vec::new();
+#[derive(Event)]
+pub struct ObjectInspected {
+    pub object: Object,
+    pub game: Game,
+    pub events: Vec<ContractEvent>,
+}
 
-        // Add the length of each vector as the first element in calldata
-        calldata.push(objects_id.len().into());
-        calldata.extend(objects_id.iter().cloned());
-        calldata.push(objects_description.len().into());
-        calldata.extend(objects_description.iter().cloned());
-        println!("{}", calldata.len());
-        thread::sleep(Duration::from_millis(250));
-        // ... concatenate elements from other vectors ...
-        match account
-            .execute(vec![Call {
-                to: FieldElement::from_hex_be(configs::ACTIONS_ADDRESS).unwrap(),
-                selector: get_selector_from_name("spawn_object").unwrap(),
-                calldata: calldata,
-            }])
-            .send()
-            .await
-        {
-            Ok(_) => {
-                println!("Objects Spawned.");
-                true
-            }
-            Err(e) => {
-                println!("Error {}", e);
-                false
-            }
-        }
-    }));
-    commands.spawn(TaskSpawnObject(task));
+#[derive(Event)]
+pub struct EscapeAttempted {
+    pub game: Game,
+    pub events: Vec<ContractEvent>,
 }
 
-fn handle_task_spawn_object(
-    mut commands: Commands,
-    mut game_task: Query<(Entity, &mut TaskSpawnObject)>,
+// the events of every finished action, in the order they were emitted
+fn forward_contract_events(
+    mut initialized: EventReader<GameInitialized>,
+    mut inspected: EventReader<ObjectInspected>,
+    mut escaped: EventReader<EscapeAttempted>,
+    mut events: EventWriter<ContractEvent>,
 ) {
-    for (entity, mut task) in &mut game_task {
-        if let Some(_) = future::block_on(future::poll_once(&mut task.0)) {
-            commands.entity(entity).remove::<TaskSpawnObject>();
-        }
+    for event in initialized.read() {
+        events.send_batch(event.events.iter().cloned());
+    }
+    for event in inspected.read() {
+        events.send_batch(event.events.iter().cloned());
     }
+    for event in escaped.read() {
+        events.send_batch(event.events.iter().cloned());
+    }
+}
+
+fn task_init(mut commands: Commands, backend: Res<Backend>) {
+    task_start_game(&mut commands, &backend, false);
 }
 
-// used to get the schema
-async fn fetch_schema(world_address: FieldElement, object_id: FieldElement, model: String) -> Ty {
-    let provider = JsonRpcClient::new(HttpTransport::new(
-        Url::parse(configs::JSON_RPC_ENDPOINT).unwrap(),
-    ));
-    let world = WorldContractReader::new(world_address, provider);
-    let position = world.model(&model).await.unwrap();
+/// Resumes the caller's unfinished game, or sends `initialise` when there is none.
+/// With `new_game` the current game is always replaced.
+pub fn task_start_game(commands: &mut Commands, backend: &Res<Backend>, new_game: bool) {
+    let backend = (*backend).clone();
 
-    if model == "Game" {
-        let object_id_slice = &[FieldElement::from_hex_be(configs::ACCOUNT_ADDRESS).unwrap()];
+    spawn_dojo_task(
+        commands,
+        async move { start_game(&backend, new_game).await },
+    );
+}
 
-        return position.entity(object_id_slice).await.unwrap();
+async fn start_game(backend: &Backend, new_game: bool) -> DojoResult<GameInitialized> {
+    if !new_game {
+        let game = backend.game().await?;
+        if game.is_initialised() && !game.is_finished && game.turns_remaining > 0 {
+            return Ok(GameInitialized {
+                game,
+                resumed: true,
+                events: Vec::new(),
+            });
+        }
     }
 
-    let object_id_slice = &[
-        FieldElement::from_hex_be(configs::ACCOUNT_ADDRESS).unwrap(),
-        object_id,
-    ];
+    let events = backend.initialise(STARTING_TURNS).await?;
+    let game = backend.game().await?;
+    Ok(GameInitialized {
+        game,
+        resumed: false,
+        events,
+    })
+}
+
+/// Plays as `config.accounts[index]` from now on, and resumes or starts its game.
+pub fn task_switch_account(
+    commands: &mut Commands,
+    backend: &Res<Backend>,
+    index: usize,
+    keys: AccountKeys,
+) {
+    let backend = (*backend).clone();
+
+    spawn_dojo_task(commands, async move {
+        backend.switch_account(keys).await?;
+        let started = start_game(&backend, false).await?;
+        Ok(AccountSwitched { index, started })
+    });
+}
+
+#[derive(Event)]
+pub struct AccountSwitched {
+    pub index: usize,
+    pub started: GameInitialized,
+}
+
+// the burner's settings and the connection it is deployed through, the game is then
+// played through the same one
+#[derive(Resource)]
+struct BurnerSetup {
+    env: Arc<DojoEnv>,
+    settings: BurnerSettings,
+}
+
+// the burner is deployed with the configured account and its game started in place of
+// the configured account's
+fn task_start_burner(mut commands: Commands, setup: Res<BurnerSetup>) {
+    let env = setup.env.clone();
+    let settings = setup.settings.clone();
 
-    position.entity(object_id_slice).await.unwrap()
+    spawn_dojo_task(&mut commands, async move {
+        let keys = burner::load_or_create(&env, &settings).await?;
+        env.switch_account(keys).await?;
+        let started = start_game(&Backend(env), false).await?;
+        Ok(BurnerStarted { keys, started })
+    });
 }
 
-// to convert hex to string
-trait ToHex {
-    fn to_hex(self) -> String;
+#[derive(Event)]
+pub struct BurnerStarted {
+    pub keys: AccountKeys,
+    pub started: GameInitialized,
 }
-impl ToHex for FieldElement {
-    fn to_hex(self) -> String {
-        format!("{:#x}", self)
+
+// the burner takes the configured account's place in `accounts`
+fn on_burner_started(
+    mut events: EventReader<BurnerStarted>,
+    mut config: ResMut<DojoConfig>,
+    mut switched: EventWriter<AccountSwitched>,
+) {
+    for event in events.read() {
+        config.replace_account(event.keys);
+        switched.send(AccountSwitched {
+            index: 0,
+            started: event.started.clone(),
+        });
+    }
+}
+
+// the models of the previous account are of no use, the new game starts as usual
+fn on_account_switched(
+    mut events: EventReader<AccountSwitched>,
+    mut active_account: ResMut<ActiveAccount>,
+    mut chain_state: ResMut<ChainState>,
+    mut initialized: EventWriter<GameInitialized>,
+) {
+    for event in events.read() {
+        active_account.0 = event.index;
+        *chain_state = ChainState::default();
+        initialized.send(event.started.clone());
     }
 }
 
+pub fn task_interact(commands: &mut Commands, backend: &Res<Backend>, object_id: FieldElement) {
+    let backend = (*backend).clone();
+
+    spawn_dojo_task(commands, async move {
+        let events = backend.interact(object_id).await?;
+        let object = backend.object(object_id).await?;
+        let game = backend.game().await?;
+        Ok(ObjectInspected {
+            object,
+            game,
+            events,
+        })
+    });
+}
+
+pub fn task_escape(commands: &mut Commands, backend: &Res<Backend>, secret: String) {
+    let backend = (*backend).clone();
+
+    spawn_dojo_task(commands, async move {
+        let secret = codec::encode_short(&secret).map_err(DojoError::encoding)?;
+        let events = backend.escape(secret).await?;
+        let game = backend.game().await?;
+        Ok(EscapeAttempted { game, events })
+    });
+}
+
+pub fn task_spawn_object(
+    commands: &mut Commands,
+    backend: &Res<Backend>,
+    objects_id: Vec<FieldElement>,
+    objects_description: Vec<String>,
+) {
+    let backend = (*backend).clone();
+    let count = objects_id.len();
+
+    spawn_dojo_task(commands, async move {
+        backend
+            .spawn_object(objects_id, objects_description)
+            .await?;
+        Ok(ObjectsSpawned { count })
+    });
+}
+
 pub fn parse_felt_value(felt: &str) -> Result<FieldElement> {
     let regex_dec_number = Regex::new("^[0-9]{1,}$").unwrap();
 
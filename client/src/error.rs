@@ -0,0 +1,96 @@
+use crate::models::DecodeError;
+use bevy::prelude::*;
+use std::fmt::Display;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DojoErrorKind {
+    // the node could not be reached or answered with garbage
+    Network,
+    // the contract rejected or reverted the transaction
+    Revert,
+    // a model read back from the world does not match its Rust mirror
+    Decode,
+    // a value could not be turned into calldata
+    Encoding,
+    // the fee is above the configured cap or the account cannot pay it
+    Fee,
+    // an action sent through a backend that cannot sign, like a spectator's
+    ReadOnly,
+    // the burner account's file could not be read or written
+    Burner,
+}
+
+impl Display for DojoErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            DojoErrorKind::Network => "network",
+            DojoErrorKind::Revert => "revert",
+            DojoErrorKind::Decode => "decode",
+            DojoErrorKind::Encoding => "encoding",
+            DojoErrorKind::Fee => "fee",
+            DojoErrorKind::ReadOnly => "read-only",
+            DojoErrorKind::Burner => "burner",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A failed call against the backend. Also sent as an event so the UI can show it.
+#[derive(Event, Clone, Debug)]
+pub struct DojoError {
+    pub kind: DojoErrorKind,
+    pub message: String,
+}
+
+pub type DojoResult<T> = Result<T, DojoError>;
+
+impl DojoError {
+    pub fn new(kind: DojoErrorKind, message: impl Display) -> Self {
+        Self {
+            kind,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn network(message: impl Display) -> Self {
+        Self::new(DojoErrorKind::Network, message)
+    }
+
+    pub fn revert(message: impl Display) -> Self {
+        Self::new(DojoErrorKind::Revert, message)
+    }
+
+    pub fn decode(message: impl Display) -> Self {
+        Self::new(DojoErrorKind::Decode, message)
+    }
+
+    pub fn encoding(message: impl Display) -> Self {
+        Self::new(DojoErrorKind::Encoding, message)
+    }
+
+    pub fn fee(message: impl Display) -> Self {
+        Self::new(DojoErrorKind::Fee, message)
+    }
+
+    pub fn read_only(message: impl Display) -> Self {
+        Self::new(DojoErrorKind::ReadOnly, message)
+    }
+
+    pub fn burner(message: impl Display) -> Self {
+        Self::new(DojoErrorKind::Burner, message)
+    }
+}
+
+impl Display for DojoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}", self.kind, self.message)
+    }
+}
+
+impl std::error::Error for DojoError {}
+
+impl From<DecodeError> for DojoError {
+    fn from(e: DecodeError) -> Self {
+        Self::decode(e)
+    }
+}
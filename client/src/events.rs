@@ -0,0 +1,117 @@
+//! Events emitted by `contract/src/actions.cairo`, see `contract/src/events.cairo`.
+//!
+//! `emit!` goes through the world contract, so the events come from the world address
+//! with the variant selector of the contract's `Event` enum as first key.
+
+use crate::codec;
+use crate::error::{DojoError, DojoResult};
+use bevy::prelude::*;
+use starknet::core::{
+    types::{Event as EmittedEvent, FieldElement},
+    utils::get_selector_from_name,
+};
+
+/// Values of `GameState.game_state`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameState {
+    Initialized,
+    CheckingItem,
+    GameOver,
+    Escaped,
+    WrongSecret,
+}
+
+impl GameState {
+    const ALL: [GameState; 5] = [
+        GameState::Initialized,
+        GameState::CheckingItem,
+        GameState::GameOver,
+        GameState::Escaped,
+        GameState::WrongSecret,
+    ];
+
+    /// The short string emitted by the contract.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GameState::Initialized => "Game Initialized",
+            GameState::CheckingItem => "Checking Item",
+            GameState::GameOver => "Game Over",
+            GameState::Escaped => "Escaped",
+            GameState::WrongSecret => "Wrong Secret",
+        }
+    }
+
+    pub fn to_felt(self) -> FieldElement {
+        // every value is a valid short string
+        codec::encode_short(self.as_str()).unwrap()
+    }
+
+    fn from_felt(value: FieldElement) -> DojoResult<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|state| state.to_felt() == value)
+            .ok_or_else(|| {
+                let value = codec::decode_short(value).unwrap_or(format!("{value:#x}"));
+                DojoError::decode(format!("unknown game state `{value}`"))
+            })
+    }
+}
+
+/// An event emitted by the `actions` contract during a transaction.
+#[derive(Event, Clone, Debug, PartialEq)]
+pub enum ContractEvent {
+    GameState(GameState),
+    ObjectData {
+        object_id: FieldElement,
+        description: String,
+    },
+}
+
+/// Decodes the `GameState` and `ObjectData` events emitted through `world_address`,
+/// in order. Events with other selectors, like the world's own, are skipped.
+pub fn decode_events(
+    world_address: FieldElement,
+    events: &[EmittedEvent],
+) -> DojoResult<Vec<ContractEvent>> {
+    let game_state = get_selector_from_name("GameState").map_err(DojoError::encoding)?;
+    let object_data = get_selector_from_name("ObjectData").map_err(DojoError::encoding)?;
+
+    events
+        .iter()
+        .filter(|event| event.from_address == world_address)
+        .filter_map(|event| {
+            let selector = *event.keys.first()?;
+
+            if selector == game_state {
+                Some(match event.data[..] {
+                    [value] => GameState::from_felt(value).map(ContractEvent::GameState),
+                    _ => Err(malformed("GameState", &event.data)),
+                })
+            } else if selector == object_data {
+                Some(object_data(&event.data))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+// `[object_id, ...description]`, the description as an array of short strings
+fn object_data(data: &[FieldElement]) -> DojoResult<ContractEvent> {
+    let Some((&object_id, description)) = data.split_first() else {
+        return Err(malformed("ObjectData", data));
+    };
+
+    match codec::decode_felt_array(description) {
+        Ok((description, used)) if used == data.len() - 1 => Ok(ContractEvent::ObjectData {
+            object_id,
+            description,
+        }),
+        Ok(_) => Err(malformed("ObjectData", data)),
+        Err(e) => Err(DojoError::decode(format!("`ObjectData` description: {e}"))),
+    }
+}
+
+fn malformed(name: &str, data: &[FieldElement]) -> DojoError {
+    DojoError::decode(format!("`{name}` event with {} data felts", data.len()))
+}
@@ -0,0 +1,114 @@
+//! Transaction fees, see [`FeePolicy`].
+
+use crate::error::{DojoError, DojoResult};
+use starknet::{
+    core::{
+        types::{BlockId, BlockTag, FieldElement, FunctionCall},
+        utils::get_selector_from_name,
+    },
+    providers::Provider,
+};
+
+// base units in one token, ETH and STRK both have 18 decimals
+const DECIMALS: u32 = 18;
+
+/// How the max fee of a transaction is derived from the node's estimate.
+///
+/// The estimate is multiplied by `multiplier` so that a small price change between the
+/// estimate and the block does not fail the transaction, then capped at `max_fee`. An
+/// estimate above the cap is refused, the transaction would not get through with less.
+#[derive(Clone, Copy, Debug)]
+pub struct FeePolicy {
+    pub multiplier: f64,
+    // in the fee token's base unit
+    pub max_fee: Option<FieldElement>,
+    // the ERC-20 contract fees are paid in
+    pub token: FieldElement,
+}
+
+/// The fee estimated for a transaction and the max fee it is sent with.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FeeQuote {
+    pub estimate: FieldElement,
+    pub max_fee: FieldElement,
+}
+
+impl FeePolicy {
+    pub fn quote(&self, overall_fee: u64) -> DojoResult<FeeQuote> {
+        let estimate = FieldElement::from(overall_fee);
+        let mut max_fee = FieldElement::from((overall_fee as f64 * self.multiplier).ceil() as u128);
+
+        if let Some(cap) = self.max_fee {
+            if estimate > cap {
+                return Err(DojoError::fee(format!(
+                    "estimated fee {} is above the max fee of {}",
+                    format_amount(estimate),
+                    format_amount(cap)
+                )));
+            }
+            max_fee = max_fee.min(cap);
+        }
+        Ok(FeeQuote { estimate, max_fee })
+    }
+
+    /// Balance of `account` in the fee token.
+    pub async fn balance<P: Provider>(
+        &self,
+        provider: &P,
+        account: FieldElement,
+    ) -> DojoResult<FieldElement> {
+        let balance = provider
+            .call(
+                FunctionCall {
+                    contract_address: self.token,
+                    entry_point_selector: get_selector_from_name("balanceOf")
+                        .map_err(DojoError::encoding)?,
+                    calldata: vec![account],
+                },
+                BlockId::Tag(BlockTag::Pending),
+            )
+            .await
+            .map_err(DojoError::network)?;
+
+        // a u256, low then high 128 bits
+        match balance[..] {
+            [low, high] => Ok(low + high * FieldElement::from(u128::MAX) + high),
+            _ => Err(DojoError::decode(format!(
+                "`balanceOf` returned {} felts",
+                balance.len()
+            ))),
+        }
+    }
+
+    /// Refuses a transaction that `account` could not pay for.
+    pub async fn check_balance<P: Provider>(
+        &self,
+        provider: &P,
+        account: FieldElement,
+        quote: &FeeQuote,
+    ) -> DojoResult<()> {
+        let balance = self.balance(provider, account).await?;
+        if balance < quote.max_fee {
+            return Err(DojoError::fee(format!(
+                "balance of {} cannot pay a max fee of {}",
+                format_amount(balance),
+                format_amount(quote.max_fee)
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// An amount of base units in whole tokens, e.g. `0.000012`.
+pub fn format_amount(amount: FieldElement) -> String {
+    let unit = 10u128.pow(DECIMALS);
+    let amount = u128::try_from(amount).unwrap_or(u128::MAX);
+
+    let fraction = format!("{:0width$}", amount % unit, width = DECIMALS as usize);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        format!("{}", amount / unit)
+    } else {
+        format!("{}.{}", amount / unit, fraction)
+    }
+}
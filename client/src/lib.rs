@@ -1,8 +1,22 @@
+pub mod backend;
+pub mod burner;
 pub mod character;
+pub mod codec;
 pub mod configs;
 pub mod dojo;
+pub mod error;
+pub mod events;
+pub mod fees;
+pub mod manifest;
+pub mod model_cache;
+pub mod models;
+pub mod offline;
 pub mod resources;
+pub mod retry;
 pub mod room;
+pub mod spectator;
+pub mod transactions;
+pub mod transport;
 pub mod ui;
 
 pub use configs::*;
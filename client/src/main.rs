@@ -3,36 +3,84 @@ use bevy::{
 };
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use game_demo::{
-    character::CharacterPlugin, dojo::DojoPlugin, resources::*, room::RoomPlugin, ui::GameUI,
+    character::CharacterPlugin,
+    configs::{DojoConfig, STARTING_TURNS},
+    dojo::DojoPlugin,
+    resources::*,
+    room::RoomPlugin,
+    ui::GameUI,
 };
+use starknet::core::types::FieldElement;
 
 fn main() {
-    App::new()
-        .add_plugins(
-            DefaultPlugins
-                .set(ImagePlugin::default_nearest())
-                .set(WindowPlugin {
-                    primary_window: Some(Window {
-                        title: "Escape from Cairo".into(),
-                        resolution: (640.0, 320.0).into(),
-                        resizable: false,
-                        ..default()
-                    }),
+    let spectating = match spectate_target() {
+        Ok(player) => player,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+
+    // spectators never sign, their keys are not needed
+    let config = match spectating {
+        Some(_) => DojoConfig::load_read_only(),
+        None => DojoConfig::load(),
+    };
+    let config = match config {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Invalid Dojo settings: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let mut app = App::new();
+    if let Some(player) = spectating {
+        app.insert_resource(Spectating {
+            player,
+            inspected: Vec::new(),
+        });
+    }
+
+    app.add_plugins(
+        DefaultPlugins
+            .set(ImagePlugin::default_nearest())
+            .set(WindowPlugin {
+                primary_window: Some(Window {
+                    title: "Escape from Cairo".into(),
+                    resolution: (640.0, 320.0).into(),
+                    resizable: false,
                     ..default()
-                })
-                .build(),
-        )
-        .insert_resource(MovesRemaining(10))
-        .register_type::<MovesRemaining>()
-        .add_plugins(
-            WorldInspectorPlugin::default().run_if(input_toggle_active(true, KeyCode::Escape)),
-        )
-        .add_plugins(GameUI)
-        .add_plugins(CharacterPlugin)
-        .add_plugins(RoomPlugin)
-        .add_plugins(DojoPlugin)
-        .add_systems(Startup, setup)
-        .run();
+                }),
+                ..default()
+            })
+            .build(),
+    )
+    .insert_resource(config)
+    .insert_resource(MovesRemaining(STARTING_TURNS))
+    .register_type::<MovesRemaining>()
+    .add_plugins(WorldInspectorPlugin::default().run_if(input_toggle_active(true, KeyCode::Escape)))
+    .add_plugins(GameUI)
+    .add_plugins(CharacterPlugin)
+    .add_plugins(RoomPlugin)
+    .add_plugins(DojoPlugin)
+    .add_systems(Startup, setup)
+    .run();
+}
+
+// `--spectate <player address>` follows that player instead of playing
+fn spectate_target() -> Result<Option<FieldElement>, String> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--spectate");
+    if args.next().is_none() {
+        return Ok(None);
+    }
+
+    match args.next() {
+        Some(address) => FieldElement::from_hex_be(&address)
+            .map(Some)
+            .map_err(|_| format!("`--spectate` needs a player address, got `{address}`")),
+        None => Err(String::from("`--spectate` needs a player address")),
+    }
 }
 
 fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
@@ -0,0 +1,103 @@
+use serde::Deserialize;
+use starknet::core::types::FieldElement;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+// name of the contract holding the game systems
+pub const ACTIONS_CONTRACT: &str = "actions";
+
+#[derive(Debug)]
+pub enum ManifestError {
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, serde_json::Error),
+    MissingWorld(PathBuf),
+    MissingContract(PathBuf, &'static str),
+    NotDeployed(PathBuf, String),
+}
+
+impl std::fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ManifestError::Io(path, e) => {
+                write!(f, "cannot read manifest {}: {}", path.display(), e)
+            }
+            ManifestError::Parse(path, e) => {
+                write!(f, "cannot parse manifest {}: {}", path.display(), e)
+            }
+            ManifestError::MissingWorld(path) => {
+                write!(f, "manifest {} has no world entry", path.display())
+            }
+            ManifestError::MissingContract(path, name) => {
+                write!(f, "manifest {} has no `{}` contract", path.display(), name)
+            }
+            ManifestError::NotDeployed(path, name) => write!(
+                f,
+                "`{}` has no address in manifest {}, run `sozo migrate` first",
+                name,
+                path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+#[derive(Deserialize)]
+struct ManifestContract {
+    name: String,
+    address: Option<FieldElement>,
+}
+
+#[derive(Deserialize)]
+struct RawManifest {
+    world: Option<ManifestContract>,
+    #[serde(default)]
+    contracts: Vec<ManifestContract>,
+}
+
+/// Addresses of a world deployed with `sozo migrate`.
+#[derive(Clone, Debug)]
+pub struct DeploymentManifest {
+    pub world_address: FieldElement,
+    pub actions_address: FieldElement,
+}
+
+impl DeploymentManifest {
+    pub fn load(path: &Path) -> Result<Self, ManifestError> {
+        let content = fs::read_to_string(path).map_err(|e| ManifestError::Io(path.into(), e))?;
+        let raw: RawManifest =
+            serde_json::from_str(&content).map_err(|e| ManifestError::Parse(path.into(), e))?;
+
+        let world = raw
+            .world
+            .ok_or_else(|| ManifestError::MissingWorld(path.into()))?;
+        let world_address = deployed_address(path, &world)?;
+
+        // contracts are listed either by name or by their full cairo path
+        let actions = raw
+            .contracts
+            .iter()
+            .find(|contract| {
+                contract.name == ACTIONS_CONTRACT
+                    || contract.name.ends_with(&format!("::{ACTIONS_CONTRACT}"))
+            })
+            .ok_or_else(|| ManifestError::MissingContract(path.into(), ACTIONS_CONTRACT))?;
+        let actions_address = deployed_address(path, actions)?;
+
+        Ok(Self {
+            world_address,
+            actions_address,
+        })
+    }
+}
+
+fn deployed_address(
+    path: &Path,
+    contract: &ManifestContract,
+) -> Result<FieldElement, ManifestError> {
+    contract
+        .address
+        .ok_or_else(|| ManifestError::NotDeployed(path.into(), contract.name.clone()))
+}
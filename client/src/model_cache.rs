@@ -0,0 +1,210 @@
+//! Model classes kept across reads, see [`ModelCache`].
+
+use crate::codec;
+use crate::error::{DojoError, DojoErrorKind, DojoResult};
+use crate::models::Model;
+use crate::retry::{RetryLog, RetryPolicy};
+use crate::transport::DojoTransport;
+use dojo_types::schema::Ty;
+use dojo_world::contracts::WorldContractReader;
+use starknet::{
+    core::{
+        types::{BlockId, BlockTag, FieldElement, FunctionCall},
+        utils::{cairo_short_string_to_felt, get_selector_from_name},
+    },
+    providers::{JsonRpcClient, Provider},
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+pub type DojoProvider = JsonRpcClient<DojoTransport>;
+
+/// Reads the world's models by name. A model's class hash, schema and layout are looked
+/// up on its first read only, later reads fetch the packed entity alone, in a single
+/// call to the world.
+///
+/// A model's class is looked up again once it no longer decodes, and when
+/// [`check_upgrade`](Self::check_upgrade) finds it registered under another class hash
+/// or the world contract upgraded.
+pub struct ModelCache {
+    world: Arc<WorldContractReader<DojoProvider>>,
+    world_address: FieldElement,
+    provider: DojoProvider,
+    classes: Mutex<HashMap<String, Arc<ModelClass>>>,
+    // class hash of the world when it was last checked
+    world_class_hash: Mutex<Option<FieldElement>>,
+}
+
+// what the class of a model tells about it, the same for every entity
+struct ModelClass {
+    name: FieldElement,
+    class_hash: FieldElement,
+    schema: Ty,
+    layout: Vec<FieldElement>,
+    packed_size: usize,
+}
+
+impl ModelCache {
+    pub fn new(world_address: FieldElement, transport: DojoTransport) -> Self {
+        let world = WorldContractReader::new(world_address, JsonRpcClient::new(transport.clone()));
+
+        Self {
+            world: Arc::new(world),
+            world_address,
+            provider: JsonRpcClient::new(transport),
+            classes: Mutex::default(),
+            world_class_hash: Mutex::default(),
+        }
+    }
+
+    /// Reads the `M` entity at `keys`, retrying network failures with `retry`.
+    pub async fn read<M: Model>(
+        &self,
+        keys: &[FieldElement],
+        retry: &RetryPolicy,
+        log: &RetryLog,
+    ) -> DojoResult<M> {
+        let schema = retry
+            .run(&format!("read {}", M::NAME), log, move || async move {
+                let class = self.class(M::NAME).await?;
+                let packed = self.entity(&class, keys).await?;
+                class.entity(keys, &packed)
+            })
+            .await;
+
+        // a model that no longer decodes was probably changed, its class is loaded again
+        let decoded = schema.and_then(|schema| Ok(M::from_ty(&schema)?));
+        if decoded
+            .as_ref()
+            .is_err_and(|e| e.kind == DojoErrorKind::Decode)
+        {
+            self.invalidate(M::NAME);
+        }
+        decoded
+    }
+
+    pub fn invalidate(&self, model: &str) {
+        self.classes.lock().unwrap().remove(model);
+    }
+
+    /// Drops every model class when the world's class hash changed since the last
+    /// check, and the class of each model the world registered again since it was
+    /// loaded. Returns whether the world was upgraded.
+    pub async fn check_upgrade(&self, provider: &DojoProvider) -> DojoResult<bool> {
+        let class_hash = provider
+            .get_class_hash_at(BlockId::Tag(BlockTag::Latest), self.world_address)
+            .await
+            .map_err(DojoError::network)?;
+
+        let upgraded = {
+            let mut known = self.world_class_hash.lock().unwrap();
+            let upgraded = known.is_some_and(|known| known != class_hash);
+            if upgraded {
+                println!(
+                    "World upgraded to class {:#x}, reloading models.",
+                    class_hash
+                );
+                self.classes.lock().unwrap().clear();
+            }
+            *known = Some(class_hash);
+            upgraded
+        };
+
+        let classes: Vec<_> = self
+            .classes
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(model, class)| (model.clone(), class.clone()))
+            .collect();
+        for (model, class) in classes {
+            let registered = self
+                .call(provider, "model", vec![class.name])
+                .await?
+                .first()
+                .copied();
+            if registered != Some(class.class_hash) {
+                self.invalidate(&model);
+            }
+        }
+        Ok(upgraded)
+    }
+
+    async fn class(&self, model: &str) -> DojoResult<Arc<ModelClass>> {
+        let cached = self.classes.lock().unwrap().get(model).cloned();
+        if let Some(class) = cached {
+            return Ok(class);
+        }
+
+        let reader = self.world.model(model).await.map_err(DojoError::network)?;
+        let layout = reader.layout().await.map_err(DojoError::network)?;
+        let class = Arc::new(ModelClass {
+            name: cairo_short_string_to_felt(model).map_err(DojoError::encoding)?,
+            class_hash: reader.class_hash(),
+            schema: reader.schema().await.map_err(DojoError::network)?,
+            packed_size: codec::packed_size(&layout).map_err(DojoError::decode)?,
+            layout,
+        });
+        self.classes
+            .lock()
+            .unwrap()
+            .insert(model.to_string(), class.clone());
+        Ok(class)
+    }
+
+    // the world's `entity`, packed values of the entity at `keys`
+    async fn entity(
+        &self,
+        class: &ModelClass,
+        keys: &[FieldElement],
+    ) -> DojoResult<Vec<FieldElement>> {
+        // model, keys, offset, length and layout
+        let mut calldata = vec![class.name, FieldElement::from(keys.len())];
+        calldata.extend_from_slice(keys);
+        calldata.extend([
+            FieldElement::ZERO,
+            FieldElement::from(class.packed_size),
+            FieldElement::from(class.layout.len()),
+        ]);
+        calldata.extend_from_slice(&class.layout);
+
+        // a `Span<felt252>`, prefixed with its length
+        let span = self.call(&self.provider, "entity", calldata).await?;
+        match span.split_first() {
+            Some((len, packed)) if *len == FieldElement::from(packed.len()) => Ok(packed.to_vec()),
+            _ => Err(DojoError::decode("malformed entity returned by the world")),
+        }
+    }
+
+    async fn call(
+        &self,
+        provider: &DojoProvider,
+        entry_point: &str,
+        calldata: Vec<FieldElement>,
+    ) -> DojoResult<Vec<FieldElement>> {
+        let call = FunctionCall {
+            contract_address: self.world_address,
+            entry_point_selector: get_selector_from_name(entry_point)
+                .map_err(DojoError::encoding)?,
+            calldata,
+        };
+        provider
+            .call(call, BlockId::Tag(BlockTag::Latest))
+            .await
+            .map_err(DojoError::network)
+    }
+}
+
+impl ModelClass {
+    // the schema filled with `keys` and the values unpacked from `packed`
+    fn entity(&self, keys: &[FieldElement], packed: &[FieldElement]) -> DojoResult<Ty> {
+        let mut values = keys.to_vec();
+        values.extend(codec::unpack(packed, &self.layout).map_err(DojoError::decode)?);
+
+        let mut schema = self.schema.clone();
+        schema.deserialize(&mut values).map_err(DojoError::decode)?;
+        Ok(schema)
+    }
+}
@@ -0,0 +1,270 @@
+//! Rust mirrors of the models declared in `contract/src/models.cairo`.
+
+use crate::error::{DojoError, DojoResult};
+use dojo_types::{
+    primitive::Primitive,
+    schema::{Member, Ty},
+};
+use starknet::core::types::FieldElement;
+
+#[derive(Debug)]
+pub enum DecodeError {
+    NotAStruct(&'static str),
+    MissingField(&'static str, &'static str),
+    TypeMismatch {
+        model: &'static str,
+        field: &'static str,
+        expected: &'static str,
+    },
+    EmptyField(&'static str, &'static str),
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::NotAStruct(model) => write!(f, "`{model}` schema is not a struct"),
+            DecodeError::MissingField(model, field) => {
+                write!(f, "`{model}` has no `{field}` member")
+            }
+            DecodeError::TypeMismatch {
+                model,
+                field,
+                expected,
+            } => write!(f, "`{model}.{field}` is not a {expected}"),
+            DecodeError::EmptyField(model, field) => write!(f, "`{model}.{field}` has no value"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// A model that can be decoded from the schema returned by the world.
+pub trait Model: Sized {
+    const NAME: &'static str;
+
+    /// The `#[key]` members, in declaration order.
+    const KEYS: &'static [KeyField];
+
+    fn from_ty(ty: &Ty) -> Result<Self, DecodeError>;
+}
+
+/// A `#[key]` member shared by several models.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyField {
+    // the account playing, `player` or `player_id`
+    Player,
+    ObjectId,
+    GameId,
+}
+
+impl std::fmt::Display for KeyField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            KeyField::Player => "player",
+            KeyField::ObjectId => "object_id",
+            KeyField::GameId => "game_id",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Entity keys of a model for one player, laid out as the model declares them.
+#[derive(Clone, Copy, Debug)]
+pub struct KeyBuilder {
+    player: FieldElement,
+    object_id: Option<FieldElement>,
+    game_id: Option<u32>,
+}
+
+impl KeyBuilder {
+    pub fn new(player: FieldElement) -> Self {
+        Self {
+            player,
+            object_id: None,
+            game_id: None,
+        }
+    }
+
+    pub fn object_id(mut self, object_id: FieldElement) -> Self {
+        self.object_id = Some(object_id);
+        self
+    }
+
+    pub fn game_id(mut self, game_id: u32) -> Self {
+        self.game_id = Some(game_id);
+        self
+    }
+
+    /// The keys of `M`, fails when one of them was not given.
+    pub fn build<M: Model>(&self) -> DojoResult<Vec<FieldElement>> {
+        M::KEYS
+            .iter()
+            .map(|key| {
+                let value = match key {
+                    KeyField::Player => Some(self.player),
+                    KeyField::ObjectId => self.object_id,
+                    KeyField::GameId => self.game_id.map(FieldElement::from),
+                };
+                value.ok_or_else(|| {
+                    DojoError::encoding(format!("`{}` is keyed by `{key}`, none given", M::NAME))
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Game {
+    pub player: FieldElement,
+    pub game_id: u32,
+    pub start_time: u64,
+    pub turns_remaining: u64,
+    pub is_finished: bool,
+}
+
+impl Game {
+    // `get!` returns a zeroed model for players that never called `initialise`
+    pub fn is_initialised(&self) -> bool {
+        self.start_time != 0
+    }
+}
+
+impl Model for Game {
+    const NAME: &'static str = "Game";
+    const KEYS: &'static [KeyField] = &[KeyField::Player];
+
+    fn from_ty(ty: &Ty) -> Result<Self, DecodeError> {
+        let fields = Fields::new(Self::NAME, ty)?;
+
+        Ok(Self {
+            player: fields.contract_address("player")?,
+            game_id: fields.u32("game_id")?,
+            start_time: fields.u64("start_time")?,
+            turns_remaining: fields.u64("turns_remaining")?,
+            is_finished: fields.bool("is_finished")?,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Object {
+    pub player: FieldElement,
+    pub object_id: FieldElement,
+    pub game_id: u32,
+    // words of the description, kept in `DescriptionWord` models the client does not read
+    pub description_len: u32,
+}
+
+impl Model for Object {
+    const NAME: &'static str = "Object";
+    const KEYS: &'static [KeyField] = &[KeyField::Player, KeyField::ObjectId];
+
+    fn from_ty(ty: &Ty) -> Result<Self, DecodeError> {
+        let fields = Fields::new(Self::NAME, ty)?;
+
+        Ok(Self {
+            player: fields.contract_address("player")?,
+            object_id: fields.felt("object_id")?,
+            game_id: fields.u32("game_id")?,
+            description_len: fields.u32("description_len")?,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Door {
+    pub game_id: u32,
+    pub player_id: FieldElement,
+    pub secret: FieldElement,
+}
+
+impl Model for Door {
+    const NAME: &'static str = "Door";
+    const KEYS: &'static [KeyField] = &[KeyField::GameId, KeyField::Player];
+
+    fn from_ty(ty: &Ty) -> Result<Self, DecodeError> {
+        let fields = Fields::new(Self::NAME, ty)?;
+
+        Ok(Self {
+            game_id: fields.u32("game_id")?,
+            player_id: fields.contract_address("player_id")?,
+            secret: fields.felt("secret")?,
+        })
+    }
+}
+
+// members of a model struct, looked up by name
+struct Fields<'a> {
+    model: &'static str,
+    children: &'a [Member],
+}
+
+impl<'a> Fields<'a> {
+    fn new(model: &'static str, ty: &'a Ty) -> Result<Self, DecodeError> {
+        match ty {
+            Ty::Struct(struct_ty) => Ok(Self {
+                model,
+                children: &struct_ty.children,
+            }),
+            _ => Err(DecodeError::NotAStruct(model)),
+        }
+    }
+
+    fn primitive(&self, field: &'static str) -> Result<&'a Primitive, DecodeError> {
+        let member = self
+            .children
+            .iter()
+            .find(|member| member.name == field)
+            .ok_or(DecodeError::MissingField(self.model, field))?;
+
+        match &member.ty {
+            Ty::Primitive(primitive) => Ok(primitive),
+            _ => Err(self.mismatch(field, "primitive")),
+        }
+    }
+
+    fn mismatch(&self, field: &'static str, expected: &'static str) -> DecodeError {
+        DecodeError::TypeMismatch {
+            model: self.model,
+            field,
+            expected,
+        }
+    }
+
+    fn felt(&self, field: &'static str) -> Result<FieldElement, DecodeError> {
+        match self.primitive(field)? {
+            Primitive::Felt252(value) => value.ok_or(DecodeError::EmptyField(self.model, field)),
+            _ => Err(self.mismatch(field, "felt252")),
+        }
+    }
+
+    fn contract_address(&self, field: &'static str) -> Result<FieldElement, DecodeError> {
+        match self.primitive(field)? {
+            Primitive::ContractAddress(value) => {
+                value.ok_or(DecodeError::EmptyField(self.model, field))
+            }
+            _ => Err(self.mismatch(field, "ContractAddress")),
+        }
+    }
+
+    fn u32(&self, field: &'static str) -> Result<u32, DecodeError> {
+        match self.primitive(field)? {
+            Primitive::U32(value) => value.ok_or(DecodeError::EmptyField(self.model, field)),
+            _ => Err(self.mismatch(field, "u32")),
+        }
+    }
+
+    fn u64(&self, field: &'static str) -> Result<u64, DecodeError> {
+        match self.primitive(field)? {
+            Primitive::U64(value) => value.ok_or(DecodeError::EmptyField(self.model, field)),
+            _ => Err(self.mismatch(field, "u64")),
+        }
+    }
+
+    fn bool(&self, field: &'static str) -> Result<bool, DecodeError> {
+        match self.primitive(field)? {
+            Primitive::Bool(value) => value.ok_or(DecodeError::EmptyField(self.model, field)),
+            _ => Err(self.mismatch(field, "bool")),
+        }
+    }
+}
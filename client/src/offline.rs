@@ -0,0 +1,225 @@
+use crate::backend::GameBackend;
+use crate::codec;
+use crate::configs::AccountKeys;
+use crate::error::{DojoError, DojoResult};
+use crate::events::{ContractEvent, GameState};
+use crate::models::{Door, Game, Object};
+use crate::resources::AccountInfo;
+use async_trait::async_trait;
+use starknet::core::types::FieldElement;
+use std::{
+    collections::HashMap,
+    sync::{Mutex, RwLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+// secret set on the door by `initialise` in actions.cairo
+const DOOR_SECRET: &str = "1984";
+
+// models stored by their keys, reads of unknown keys return zeroed models like `get!`
+#[derive(Default)]
+struct OfflineWorld {
+    uuid: u32,
+    games: HashMap<FieldElement, Game>,
+    objects: HashMap<(FieldElement, FieldElement), Object>,
+    // the `DescriptionWord` models of each object, joined
+    descriptions: HashMap<(FieldElement, FieldElement), String>,
+    doors: HashMap<(u32, FieldElement), Door>,
+}
+
+impl OfflineWorld {
+    fn uuid(&mut self) -> u32 {
+        let id = self.uuid;
+        self.uuid += 1;
+        id
+    }
+
+    fn game(&self, player: FieldElement) -> Game {
+        self.games.get(&player).copied().unwrap_or(Game {
+            player,
+            ..Default::default()
+        })
+    }
+}
+
+/// In-memory copy of `contract/src/actions.cairo`, used to play the room without Katana.
+pub struct OfflineBackend {
+    player: RwLock<FieldElement>,
+    world: Mutex<OfflineWorld>,
+}
+
+impl OfflineBackend {
+    pub fn new(player: FieldElement) -> Self {
+        Self {
+            player: RwLock::new(player),
+            world: Mutex::new(OfflineWorld::default()),
+        }
+    }
+
+    fn player(&self) -> FieldElement {
+        *self.player.read().unwrap()
+    }
+
+    // decrements the caller's turns, `None` once the game is over
+    fn take_turn(&self, world: &mut OfflineWorld) -> Option<Game> {
+        let mut game = world.game(self.player());
+
+        if game.turns_remaining == 0 {
+            return None;
+        }
+        game.turns_remaining -= 1;
+        Some(game)
+    }
+}
+
+#[async_trait]
+impl GameBackend for OfflineBackend {
+    async fn initialise(&self, turns_remaining: u64) -> DojoResult<Vec<ContractEvent>> {
+        let mut world = self.world.lock().unwrap();
+        let game_id = world.uuid();
+        let start_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_secs())
+            .unwrap_or_default();
+
+        world.games.insert(
+            self.player(),
+            Game {
+                player: self.player(),
+                game_id,
+                start_time,
+                turns_remaining,
+                is_finished: false,
+            },
+        );
+        world.doors.insert(
+            (game_id, self.player()),
+            Door {
+                game_id,
+                player_id: self.player(),
+                secret: codec::encode_short(DOOR_SECRET).map_err(DojoError::encoding)?,
+            },
+        );
+        Ok(vec![ContractEvent::GameState(GameState::Initialized)])
+    }
+
+    async fn spawn_object(
+        &self,
+        objects_id: Vec<FieldElement>,
+        objects_description: Vec<String>,
+    ) -> DojoResult<Vec<ContractEvent>> {
+        if objects_id.len() != objects_description.len() {
+            return Err(DojoError::revert("Array not equal."));
+        }
+
+        let mut world = self.world.lock().unwrap();
+        let game = world.game(self.player());
+
+        for (object_id, description) in objects_id.into_iter().zip(objects_description) {
+            world.objects.insert(
+                (self.player(), object_id),
+                Object {
+                    player: self.player(),
+                    object_id,
+                    game_id: game.game_id,
+                    description_len: description.len().div_ceil(codec::WORD_LEN) as u32,
+                },
+            );
+            world
+                .descriptions
+                .insert((self.player(), object_id), description);
+        }
+        Ok(Vec::new())
+    }
+
+    async fn interact(&self, object_id: FieldElement) -> DojoResult<Vec<ContractEvent>> {
+        let mut world = self.world.lock().unwrap();
+
+        let Some(game) = self.take_turn(&mut world) else {
+            return Ok(vec![ContractEvent::GameState(GameState::GameOver)]);
+        };
+        world.games.insert(self.player(), game);
+
+        let description = world
+            .descriptions
+            .get(&(self.player(), object_id))
+            .cloned()
+            .unwrap_or_default();
+        Ok(vec![
+            ContractEvent::GameState(GameState::CheckingItem),
+            ContractEvent::ObjectData {
+                object_id,
+                description,
+            },
+        ])
+    }
+
+    async fn escape(&self, secret: FieldElement) -> DojoResult<Vec<ContractEvent>> {
+        let mut world = self.world.lock().unwrap();
+
+        let Some(mut game) = self.take_turn(&mut world) else {
+            return Ok(vec![ContractEvent::GameState(GameState::GameOver)]);
+        };
+
+        let door = world
+            .doors
+            .get(&(game.game_id, self.player()))
+            .copied()
+            .unwrap_or_default();
+
+        let state = if door.secret == secret {
+            game.is_finished = true;
+            GameState::Escaped
+        } else {
+            GameState::WrongSecret
+        };
+        world.games.insert(self.player(), game);
+        Ok(vec![ContractEvent::GameState(state)])
+    }
+
+    async fn switch_account(&self, keys: AccountKeys) -> DojoResult<()> {
+        *self.player.write().unwrap() = keys.address;
+        Ok(())
+    }
+
+    async fn account_info(&self) -> DojoResult<AccountInfo> {
+        // nothing is signed or paid for offline
+        Ok(AccountInfo {
+            address: self.player(),
+            nonce: FieldElement::ZERO,
+            balance: None,
+        })
+    }
+
+    async fn game(&self) -> DojoResult<Game> {
+        Ok(self.world.lock().unwrap().game(self.player()))
+    }
+
+    async fn object(&self, object_id: FieldElement) -> DojoResult<Object> {
+        let world = self.world.lock().unwrap();
+
+        Ok(world
+            .objects
+            .get(&(self.player(), object_id))
+            .copied()
+            .unwrap_or(Object {
+                player: self.player(),
+                object_id,
+                ..Default::default()
+            }))
+    }
+
+    async fn door(&self, game_id: u32) -> DojoResult<Door> {
+        let world = self.world.lock().unwrap();
+
+        Ok(world
+            .doors
+            .get(&(game_id, self.player()))
+            .copied()
+            .unwrap_or(Door {
+                game_id,
+                player_id: self.player(),
+                ..Default::default()
+            }))
+    }
+}
@@ -1,4 +1,6 @@
-use bevy::prelude::*;
+use crate::models::{Game, Object};
+use bevy::{prelude::*, utils::HashMap};
+use starknet::core::types::FieldElement;
 
 #[derive(Resource, Default, Reflect)]
 #[reflect(Resource)]
@@ -7,3 +9,63 @@ pub struct MovesRemaining(pub u64);
 #[derive(Resource, Default, Reflect)]
 #[reflect(Resource)]
 pub struct ObjectNameInteraction(pub String);
+
+/// Latest models read from the world by the sync loop.
+#[derive(Resource, Default)]
+pub struct ChainState {
+    pub game: Option<Game>,
+    pub objects: HashMap<FieldElement, Object>,
+}
+
+/// The room's objects as spawned on-chain, also the `Object` models that are synced.
+#[derive(Resource, Default)]
+pub struct RoomObjects {
+    pub ids: Vec<FieldElement>,
+    pub descriptions: Vec<String>,
+}
+
+/// Index in `DojoConfig::accounts` of the account being played.
+#[derive(Resource, Default)]
+pub struct ActiveAccount(pub usize);
+
+/// The player followed in spectator mode (`--spectate <address>`), where every action
+/// is disabled. Not inserted when playing.
+#[derive(Resource, Clone, Debug)]
+pub struct Spectating {
+    pub player: FieldElement,
+    // objects the player inspected since spectating started, oldest first
+    pub inspected: Vec<FieldElement>,
+}
+
+/// Address, nonce and fee-token balance of the account being played. Inserted once
+/// first read, then read again after every confirmed transaction and account switch.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct AccountInfo {
+    pub address: FieldElement,
+    pub nonce: FieldElement,
+    // `None` for backends without a fee token
+    pub balance: Option<FieldElement>,
+}
+
+/// Reachability of the node, updated by the periodic health check.
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub enum DojoConnectionStatus {
+    // no check has finished yet
+    #[default]
+    Connecting,
+    Connected {
+        block_number: u64,
+    },
+    // the node answers, but slowly or only in part
+    Degraded {
+        reason: String,
+    },
+    Disconnected {
+        reason: String,
+    },
+    // the node serves another chain than the configured one
+    WrongChain {
+        expected: FieldElement,
+        actual: FieldElement,
+    },
+}
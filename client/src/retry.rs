@@ -0,0 +1,96 @@
+//! Retries for chain calls that failed in transport, see [`RetryPolicy`].
+
+use crate::error::{DojoError, DojoErrorKind, DojoResult};
+use bevy::prelude::*;
+use rand::Rng;
+use std::{future::Future, sync::Mutex, time::Duration};
+
+/// How often and how long to wait before running a failed call again.
+///
+/// Only [`DojoErrorKind::Network`] failures are retried, a revert would fail the same way.
+/// The delay doubles with every attempt up to `max_delay`, and a random part of up to
+/// half of it is taken off so that clients do not retry in lockstep.
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `attempt`, counted from 1.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        let delay = self
+            .base_delay
+            .saturating_mul(factor)
+            .min(self.max_delay)
+            .as_millis() as u64;
+
+        Duration::from_millis(rand::thread_rng().gen_range(delay / 2..=delay))
+    }
+
+    /// Runs `call` until it succeeds, fails with anything but a network error, or
+    /// `max_retries` is reached. Every retry is added to `log`.
+    pub async fn run<T, F, Fut>(
+        &self,
+        operation: &str,
+        log: &RetryLog,
+        mut call: F,
+    ) -> DojoResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = DojoResult<T>>,
+    {
+        let mut attempt = 0;
+
+        loop {
+            match call().await {
+                Err(error)
+                    if error.kind == DojoErrorKind::Network && attempt < self.max_retries =>
+                {
+                    attempt += 1;
+                    let delay = self.delay(attempt);
+                    log.push(RetryAttempted {
+                        operation: operation.to_string(),
+                        attempt,
+                        max_retries: self.max_retries,
+                        delay,
+                        error,
+                    });
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// A call that failed and is about to be run again.
+#[derive(Event, Clone, Debug)]
+pub struct RetryAttempted {
+    pub operation: String,
+    // 1 for the first retry
+    pub attempt: u32,
+    pub max_retries: u32,
+    pub delay: Duration,
+    pub error: DojoError,
+}
+
+/// Retries made by background calls, drained into [`RetryAttempted`] events every frame.
+#[derive(Default)]
+pub struct RetryLog(Mutex<Vec<RetryAttempted>>);
+
+impl RetryLog {
+    fn push(&self, retry: RetryAttempted) {
+        println!(
+            "Retrying {} ({}/{}) in {:?}: {}",
+            retry.operation, retry.attempt, retry.max_retries, retry.delay, retry.error
+        );
+        self.0.lock().unwrap().push(retry);
+    }
+
+    pub fn drain(&self) -> Vec<RetryAttempted> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
@@ -1,9 +1,18 @@
+use crate::backend::Backend;
 use crate::character::Player;
-use crate::dojo::{task_escape, task_interact, task_spawn_object, DojoEnv};
+use crate::codec;
+use crate::configs::DojoConfig;
+use crate::dojo::{
+    task_escape, task_interact, task_spawn_object, task_start_game, task_switch_account,
+    AccountSwitched, BurnerStarted, DojoTask, EscapeAttempted, GameInitialized, ObjectInspected,
+    ObjectsSpawned,
+};
+use crate::error::DojoError;
+use crate::events::{ContractEvent, GameState};
 use crate::resources::*;
 use bevy::{prelude::*, sprite::*};
 use bevy_inspector_egui::InspectorOptions;
-use starknet::core::{types::FieldElement, utils::cairo_short_string_to_felt};
+use starknet::core::types::FieldElement;
 
 pub struct RoomPlugin;
 pub struct SpawnRoom;
@@ -12,7 +21,18 @@ impl Plugin for RoomPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(ObjectNameInteraction(String::from("")))
             .add_systems(Startup, setup)
-            .add_systems(Update, highlight_object);
+            .add_systems(
+                Update,
+                (
+                    // spectators only watch
+                    (highlight_object, new_game, switch_account)
+                        .run_if(not(resource_exists::<Spectating>())),
+                    on_game_initialized,
+                    on_object_inspected,
+                    on_escape_attempted,
+                    on_contract_event,
+                ),
+            );
     }
 }
 #[derive(Component, InspectorOptions, Default, Reflect)]
@@ -22,7 +42,12 @@ pub struct Object {
     pub name: String,
 }
 
-fn setup(mut commands: Commands, asset_server: Res<AssetServer>, env: Res<DojoEnv>) {
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut room_objects: ResMut<RoomObjects>,
+    mut errors: EventWriter<DojoError>,
+) {
     // loading the assets
     // TODO: Load it as a SpriteBundle
     let bookcase_texture = asset_server.load("object_bookcase.png");
@@ -38,7 +63,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, env: Res<DojoEn
             bookcase_texture,
             Transform::from_xyz(-40.0, -40.0, 0.0),
             "Bookcase",
-            "A strange book, 1984",
+            "A strange book, 1984, with a page torn out of the middle.",
         ),
         (
             cupboard_texture,
@@ -96,29 +121,36 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, env: Res<DojoEn
             }),
     );
 
-    // Spawn each object on the dojo side.
+    // Objects spawned on the dojo side once a new game is initialized.
 
-    // Create a new vector with only the last two elements of each tuple
-    let objects_data: Vec<(FieldElement, FieldElement)> = objects
+    // Create a new vector with only the last two elements of each tuple,
+    // objects whose name cannot be encoded are reported and left off-chain. Names are
+    // short strings, descriptions can be of any length.
+    let objects_data: Vec<(FieldElement, String)> = objects
         .iter()
-        .map(|&(_, _, a, b)| {
-            (
-                cairo_short_string_to_felt(a).unwrap(),
-                cairo_short_string_to_felt(b).unwrap(),
-            )
+        .filter_map(|&(_, _, a, b)| match codec::encode_short(a) {
+            Ok(id) => Some((id, b.to_string())),
+            Err(e) => {
+                errors.send(DojoError::encoding(format!("object `{a}`: {e}")));
+                None
+            }
         })
         .collect();
 
     // Separate objects_ids and objects_descriptions vectors
-    let objects_ids: Vec<FieldElement> = objects_data.iter().map(|(id, _)| id.clone()).collect();
-    let objects_descriptions: Vec<FieldElement> = objects_data
+    room_objects.ids = objects_data.iter().map(|(id, _)| id.clone()).collect();
+    room_objects.descriptions = objects_data
         .iter()
         .map(|(_, description)| description.clone())
         .collect();
-
-    task_spawn_object(&mut commands, &env, objects_ids, objects_descriptions);
 }
 
+// an account switch in flight, the burner taking the configured account's place included
+type Switching = Or<(
+    With<DojoTask<AccountSwitched>>,
+    With<DojoTask<BurnerStarted>>,
+)>;
+
 fn highlight_object(
     mut commands: Commands,
     mut objects: Query<((Entity, &Transform, &Handle<Image>, &Name), With<Object>)>,
@@ -128,7 +160,9 @@ fn highlight_object(
     mut evr_char: EventReader<ReceivedCharacter>,
     kbd: Res<Input<KeyCode>>,
     mut string: Local<String>,
-    env: Res<DojoEnv>,
+    backend: Res<Backend>,
+    mut errors: EventWriter<DojoError>,
+    switching: Query<(), Switching>,
 ) {
     let character_transform = characters.single_mut();
 
@@ -145,17 +179,17 @@ fn highlight_object(
         let character_x = character_transform.0.translation.x;
 
         if character_x > object_min && character_x < object_max {
-            if input.just_pressed(KeyCode::E) {
+            // the action would be signed by one account and read back from the other
+            if input.just_pressed(KeyCode::E) && switching.is_empty() {
                 if obj_name.to_string() == "Door" {
                     println!("The secret to open the door is: {}", &*string);
-                    task_escape(&mut commands, &env, string.to_string());
+                    task_escape(&mut commands, &backend, string.to_string());
                     return;
                 }
-                task_interact(
-                    &mut commands,
-                    &env,
-                    cairo_short_string_to_felt(obj_name).unwrap(),
-                );
+                match codec::encode_short(obj_name) {
+                    Ok(object_id) => task_interact(&mut commands, &backend, object_id),
+                    Err(e) => errors.send(DojoError::encoding(format!("object `{obj_name}`: {e}"))),
+                }
             }
         }
     }
@@ -174,3 +208,113 @@ fn highlight_object(
         }
     }
 }
+
+fn on_game_initialized(
+    mut commands: Commands,
+    mut events: EventReader<GameInitialized>,
+    mut moves: ResMut<MovesRemaining>,
+    room_objects: Res<RoomObjects>,
+    backend: Res<Backend>,
+) {
+    for event in events.read() {
+        moves.0 = event.game.turns_remaining;
+
+        if !event.resumed {
+            task_spawn_object(
+                &mut commands,
+                &backend,
+                room_objects.ids.clone(),
+                room_objects.descriptions.clone(),
+            );
+        }
+    }
+}
+
+// Ctrl+N starts over with a fresh game. A plain N is part of the door secret being typed.
+fn new_game(
+    mut commands: Commands,
+    input: Res<Input<KeyCode>>,
+    backend: Res<Backend>,
+    starting: Query<(), With<DojoTask<GameInitialized>>>,
+    switching: Query<(), Switching>,
+) {
+    let ctrl = input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if !ctrl || !input.just_pressed(KeyCode::N) {
+        return;
+    }
+    if !starting.is_empty() || !switching.is_empty() {
+        return;
+    }
+
+    task_start_game(&mut commands, &backend, true);
+}
+
+// Tab plays as the next account from the settings, once the running actions are done:
+// they read their results back from the account playing
+fn switch_account(
+    mut commands: Commands,
+    input: Res<Input<KeyCode>>,
+    backend: Res<Backend>,
+    config: Res<DojoConfig>,
+    active_account: Res<ActiveAccount>,
+    switching: Query<(), Switching>,
+    acting: Query<
+        (),
+        Or<(
+            With<DojoTask<GameInitialized>>,
+            With<DojoTask<ObjectsSpawned>>,
+            With<DojoTask<ObjectInspected>>,
+            With<DojoTask<EscapeAttempted>>,
+        )>,
+    >,
+) {
+    if !input.just_pressed(KeyCode::Tab) || config.accounts.len() < 2 {
+        return;
+    }
+    if !switching.is_empty() || !acting.is_empty() {
+        return;
+    }
+
+    let index = (active_account.0 + 1) % config.accounts.len();
+    task_switch_account(&mut commands, &backend, index, config.accounts[index]);
+}
+
+fn on_object_inspected(
+    mut events: EventReader<ObjectInspected>,
+    mut moves: ResMut<MovesRemaining>,
+) {
+    for event in events.read() {
+        println!("Turns remaining: {}", event.game.turns_remaining);
+        moves.0 = event.game.turns_remaining;
+    }
+}
+
+fn on_escape_attempted(
+    mut events: EventReader<EscapeAttempted>,
+    mut moves: ResMut<MovesRemaining>,
+) {
+    for event in events.read() {
+        println!("Turns remaining: {}", event.game.turns_remaining);
+        moves.0 = event.game.turns_remaining;
+    }
+}
+
+// outcomes as reported by the contract
+fn on_contract_event(mut events: EventReader<ContractEvent>) {
+    for event in events.read() {
+        match event {
+            ContractEvent::GameState(GameState::Initialized) => println!("Game Initialized."),
+            ContractEvent::GameState(GameState::CheckingItem) => {}
+            ContractEvent::GameState(GameState::GameOver) => println!("Game Over"),
+            ContractEvent::GameState(GameState::Escaped) => {
+                println!("You have escaped the room!")
+            }
+            ContractEvent::GameState(GameState::WrongSecret) => {
+                println!("Wrong secret. Try again.")
+            }
+            ContractEvent::ObjectData { description, .. } => {
+                println!("Object description: {description}")
+            }
+        }
+    }
+}
@@ -0,0 +1,180 @@
+//! Read-only backend following another player, see [`SpectatorBackend`].
+
+use crate::backend::GameBackend;
+use crate::configs::{AccountKeys, DojoConfig};
+use crate::dojo::node_health;
+use crate::error::{DojoError, DojoResult};
+use crate::events::ContractEvent;
+use crate::model_cache::{DojoProvider, ModelCache};
+use crate::models::{Door, Game, KeyBuilder, Model, Object};
+use crate::resources::{AccountInfo, DojoConnectionStatus};
+use crate::retry::{RetryAttempted, RetryLog};
+use crate::transport::{DojoTransport, TransportError};
+use async_trait::async_trait;
+use starknet::{
+    core::{
+        types::{BlockId, BlockTag, EventFilter, FieldElement},
+        utils::get_selector_from_name,
+    },
+    providers::{JsonRpcClient, Provider},
+};
+use std::sync::Mutex;
+
+// events read per `starknet_getEvents` page
+const EVENTS_CHUNK_SIZE: u64 = 100;
+
+/// Reads the models of `player` through the world, without an account. Every action
+/// fails with [`DojoErrorKind::ReadOnly`](crate::error::DojoErrorKind::ReadOnly).
+///
+/// Inspections leave no trace in the models, they are found from the `ObjectData`
+/// events keyed by `player` since spectating started.
+pub struct SpectatorBackend {
+    player: FieldElement,
+    provider: DojoProvider,
+    models: ModelCache,
+    retries: RetryLog,
+    // first block whose events have not been read yet, `None` before the first poll
+    next_block: Mutex<Option<u64>>,
+    config: DojoConfig,
+}
+
+impl SpectatorBackend {
+    pub fn from_config(config: DojoConfig, player: FieldElement) -> Result<Self, TransportError> {
+        let transport = DojoTransport::from_config(&config)?;
+
+        Ok(Self {
+            player,
+            provider: JsonRpcClient::new(transport.clone()),
+            models: ModelCache::new(config.world_address, transport),
+            retries: RetryLog::default(),
+            next_block: Mutex::default(),
+            config,
+        })
+    }
+
+    async fn read<M: Model>(&self, keys: KeyBuilder) -> DojoResult<M> {
+        self.models
+            .read(&keys.build::<M>()?, &self.config.retry, &self.retries)
+            .await
+    }
+
+    fn keys(&self) -> KeyBuilder {
+        KeyBuilder::new(self.player)
+    }
+}
+
+fn read_only() -> DojoError {
+    DojoError::read_only("spectators cannot send transactions")
+}
+
+#[async_trait]
+impl GameBackend for SpectatorBackend {
+    async fn initialise(&self, _turns_remaining: u64) -> DojoResult<Vec<ContractEvent>> {
+        Err(read_only())
+    }
+
+    async fn spawn_object(
+        &self,
+        _objects_id: Vec<FieldElement>,
+        _objects_description: Vec<String>,
+    ) -> DojoResult<Vec<ContractEvent>> {
+        Err(read_only())
+    }
+
+    async fn interact(&self, _object_id: FieldElement) -> DojoResult<Vec<ContractEvent>> {
+        Err(read_only())
+    }
+
+    async fn escape(&self, _secret: FieldElement) -> DojoResult<Vec<ContractEvent>> {
+        Err(read_only())
+    }
+
+    async fn switch_account(&self, _keys: AccountKeys) -> DojoResult<()> {
+        Err(read_only())
+    }
+
+    async fn account_info(&self) -> DojoResult<AccountInfo> {
+        let nonce = self
+            .provider
+            .get_nonce(BlockId::Tag(BlockTag::Pending), self.player)
+            .await
+            .map_err(DojoError::network)?;
+        let balance = self.config.fee.balance(&self.provider, self.player).await?;
+
+        Ok(AccountInfo {
+            address: self.player,
+            nonce,
+            balance: Some(balance),
+        })
+    }
+
+    async fn game(&self) -> DojoResult<Game> {
+        self.read(self.keys()).await
+    }
+
+    async fn object(&self, object_id: FieldElement) -> DojoResult<Object> {
+        self.read(self.keys().object_id(object_id)).await
+    }
+
+    async fn door(&self, game_id: u32) -> DojoResult<Door> {
+        self.read(self.keys().game_id(game_id)).await
+    }
+
+    async fn health(&self) -> DojoConnectionStatus {
+        node_health(&self.provider, &self.models, self.config.chain_id).await
+    }
+
+    async fn inspected_objects(&self) -> DojoResult<Vec<FieldElement>> {
+        let latest = self
+            .provider
+            .block_number()
+            .await
+            .map_err(DojoError::network)?;
+        let next_block = *self.next_block.lock().unwrap();
+        let from_block = match next_block {
+            Some(from_block) if from_block <= latest => from_block,
+            Some(_) => return Ok(Vec::new()),
+            // earlier inspections may belong to finished games, follow from here
+            None => {
+                *self.next_block.lock().unwrap() = Some(latest + 1);
+                return Ok(Vec::new());
+            }
+        };
+
+        let filter = EventFilter {
+            from_block: Some(BlockId::Number(from_block)),
+            to_block: Some(BlockId::Number(latest)),
+            address: Some(self.config.world_address),
+            // the player is the first key after the selector
+            keys: Some(vec![
+                vec![get_selector_from_name("ObjectData").map_err(DojoError::encoding)?],
+                vec![self.player],
+            ]),
+        };
+
+        let mut inspected = Vec::new();
+        let mut continuation_token = None;
+        loop {
+            let page = self
+                .provider
+                .get_events(filter.clone(), continuation_token, EVENTS_CHUNK_SIZE)
+                .await
+                .map_err(DojoError::network)?;
+
+            inspected.extend(page.events.iter().filter_map(|event| event.data.first()));
+
+            continuation_token = page.continuation_token;
+            if continuation_token.is_none() {
+                break;
+            }
+        }
+
+        // only moved on once every page was read, a failed poll is read again
+        *self.next_block.lock().unwrap() = Some(latest + 1);
+        Ok(inspected)
+    }
+
+    fn take_retries(&self) -> Vec<RetryAttempted> {
+        self.retries.drain()
+    }
+}
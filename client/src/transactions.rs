@@ -0,0 +1,138 @@
+//! Ordering and bookkeeping for the transactions sent by [`DojoEnv`](crate::dojo::DojoEnv).
+
+use crate::fees::FeeQuote;
+use bevy::prelude::*;
+use starknet::core::types::FieldElement;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Mutex,
+};
+
+// finished transactions kept around for the HUD
+const FINISHED_HISTORY: usize = 16;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransactionStatus {
+    // waiting for its turn, sent or waiting for its receipt
+    Pending,
+    Confirmed,
+    Failed(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct QueuedTransaction {
+    pub id: u64,
+    pub entrypoint: String,
+    // known once the node accepted the transaction
+    pub transaction_hash: Option<FieldElement>,
+    // known once the fee has been estimated
+    pub fee: Option<FeeQuote>,
+    pub status: TransactionStatus,
+}
+
+/// Serializes submissions so that concurrent actions do not race on the account nonce.
+///
+/// The nonce is tracked locally and only read from the node when it is unknown: on the
+/// first submission, after the node refused one, and when it reports the nonce as
+/// already used. A submission holds the nonce across all its retries, so a send lost in
+/// transport goes out again before any later action signs with the same nonce.
+#[derive(Default)]
+pub struct TransactionQueue {
+    // `None` until the nonce has been read from the node
+    nonce: tokio::sync::Mutex<Option<FieldElement>>,
+    next_id: AtomicU64,
+    log: Mutex<Vec<QueuedTransaction>>,
+}
+
+impl TransactionQueue {
+    /// Locks the nonce until the guard is dropped. Callers are served in the order they
+    /// asked, so transactions are sent in the order the actions were taken.
+    pub async fn nonce(&self) -> tokio::sync::MutexGuard<'_, Option<FieldElement>> {
+        self.nonce.lock().await
+    }
+
+    /// Adds a pending entry for a call to `entrypoint` and returns its id.
+    pub fn push(&self, entrypoint: &str) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.log.lock().unwrap().push(QueuedTransaction {
+            id,
+            entrypoint: entrypoint.to_string(),
+            transaction_hash: None,
+            fee: None,
+            status: TransactionStatus::Pending,
+        });
+        id
+    }
+
+    pub fn quoted(&self, id: u64, fee: FeeQuote) {
+        self.update(id, |transaction| transaction.fee = Some(fee));
+    }
+
+    pub fn sent(&self, id: u64, transaction_hash: FieldElement) {
+        self.update(id, |transaction| {
+            transaction.transaction_hash = Some(transaction_hash)
+        });
+    }
+
+    pub fn finish(&self, id: u64, status: TransactionStatus) {
+        self.update(id, |transaction| transaction.status = status);
+
+        let mut log = self.log.lock().unwrap();
+        let finished = log
+            .iter()
+            .filter(|transaction| transaction.status != TransactionStatus::Pending)
+            .count();
+        let mut excess = finished.saturating_sub(FINISHED_HISTORY);
+        log.retain(|transaction| {
+            let drop = excess > 0 && transaction.status != TransactionStatus::Pending;
+            if drop {
+                excess -= 1;
+            }
+            !drop
+        });
+    }
+
+    /// Every pending transaction and the last finished ones, oldest first.
+    pub fn snapshot(&self) -> Vec<QueuedTransaction> {
+        self.log.lock().unwrap().clone()
+    }
+
+    fn update(&self, id: u64, f: impl FnOnce(&mut QueuedTransaction)) {
+        if let Some(transaction) = self
+            .log
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|transaction| transaction.id == id)
+        {
+            f(transaction);
+        }
+    }
+}
+
+/// Status of the transactions sent by the backend, refreshed every frame.
+#[derive(Resource, Default)]
+pub struct Transactions(pub Vec<QueuedTransaction>);
+
+impl Transactions {
+    pub fn pending(&self) -> usize {
+        self.0
+            .iter()
+            .filter(|transaction| transaction.status == TransactionStatus::Pending)
+            .count()
+    }
+
+    /// Id of the latest confirmed transaction.
+    pub fn latest_confirmed(&self) -> Option<u64> {
+        self.0
+            .iter()
+            .filter(|transaction| transaction.status == TransactionStatus::Confirmed)
+            .map(|transaction| transaction.id)
+            .max()
+    }
+
+    /// Fee of the latest transaction that got an estimate.
+    pub fn latest_fee(&self) -> Option<FeeQuote> {
+        self.0.iter().rev().find_map(|transaction| transaction.fee)
+    }
+}
@@ -0,0 +1,207 @@
+//! JSON-RPC transport that can record a session to a file and replay it later
+//! without a node, so bug reports can be reproduced and kept as fixtures.
+
+use crate::configs::DojoConfig;
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use starknet::providers::jsonrpc::{
+    HttpTransport, HttpTransportError, JsonRpcMethod, JsonRpcResponse, JsonRpcTransport,
+};
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+use url::Url;
+
+#[derive(Debug)]
+pub enum TransportError {
+    Http(HttpTransportError),
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    // replay has no unused answer for this method and params
+    NotRecorded(String),
+    // recording would overwrite an earlier session
+    RecordingExists(PathBuf),
+}
+
+impl std::fmt::Display for TransportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransportError::Http(e) => write!(f, "{e}"),
+            TransportError::Io(e) => write!(f, "cannot access the recording: {e}"),
+            TransportError::Json(e) => write!(f, "invalid JSON-RPC payload: {e}"),
+            TransportError::NotRecorded(method) => {
+                write!(f, "the recording has no answer left for {method}")
+            }
+            TransportError::RecordingExists(path) => write!(
+                f,
+                "{} already exists, remove it or record to another file",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TransportError {}
+
+impl From<HttpTransportError> for TransportError {
+    fn from(e: HttpTransportError) -> Self {
+        Self::Http(e)
+    }
+}
+
+impl From<std::io::Error> for TransportError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for TransportError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+// one line of a recording
+#[derive(Serialize, Deserialize)]
+struct Exchange {
+    method: Value,
+    params: Value,
+    response: Value,
+}
+
+enum Mode {
+    Http(HttpTransport),
+    Record {
+        http: HttpTransport,
+        file: Mutex<BufWriter<File>>,
+    },
+    // `None` once an exchange has been served
+    Replay(Mutex<Vec<Option<Exchange>>>),
+}
+
+/// Transport shared by every provider of a [`DojoEnv`](crate::dojo::DojoEnv).
+///
+/// Recordings are JSON lines of request/response pairs. On replay a request is
+/// answered by the first unused pair with the same method and params, so tasks
+/// running concurrently get the same answers regardless of their order.
+#[derive(Clone)]
+pub struct DojoTransport(Arc<Mode>);
+
+impl DojoTransport {
+    pub fn http(url: Url) -> Self {
+        Self(Arc::new(Mode::Http(HttpTransport::new(url))))
+    }
+
+    /// Forwards to `url` and appends every exchange to `path`, which must not exist yet.
+    pub fn record(url: Url, path: &Path) -> Result<Self, TransportError> {
+        let file = File::options()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .map_err(|e| match e.kind() {
+                io::ErrorKind::AlreadyExists => TransportError::RecordingExists(path.into()),
+                _ => TransportError::Io(e),
+            })?;
+        let file = BufWriter::new(file);
+
+        Ok(Self(Arc::new(Mode::Record {
+            http: HttpTransport::new(url),
+            file: Mutex::new(file),
+        })))
+    }
+
+    /// Answers from the recording at `path` without contacting a node.
+    pub fn replay(path: &Path) -> Result<Self, TransportError> {
+        let exchanges = fs::read_to_string(path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map(Some))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self(Arc::new(Mode::Replay(Mutex::new(exchanges)))))
+    }
+
+    /// Replays or records when `rpc_replay` or `rpc_record` is set, otherwise plain HTTP.
+    pub fn from_config(config: &DojoConfig) -> Result<Self, TransportError> {
+        match (&config.rpc_replay, &config.rpc_record) {
+            (Some(path), _) => Self::replay(path),
+            (None, Some(path)) => Self::record(config.json_rpc_endpoint.clone(), path),
+            (None, None) => Ok(Self::http(config.json_rpc_endpoint.clone())),
+        }
+    }
+}
+
+#[async_trait]
+impl JsonRpcTransport for DojoTransport {
+    type Error = TransportError;
+
+    async fn send_request<P, R>(
+        &self,
+        method: JsonRpcMethod,
+        params: P,
+    ) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        match &*self.0 {
+            Mode::Http(http) => Ok(http.send_request(method, params).await?),
+            Mode::Record { http, file } => {
+                let params = serde_json::to_value(&params)?;
+                let response = http
+                    .send_request::<_, Value>(method, &params)
+                    .await
+                    .map(response_to_json)?;
+
+                let exchange = Exchange {
+                    method: serde_json::to_value(method)?,
+                    params,
+                    response: response.clone(),
+                };
+                let mut file = file.lock().unwrap();
+                serde_json::to_writer(&mut *file, &exchange)?;
+                writeln!(file)?;
+                // keep the recording usable if the game is killed
+                file.flush()?;
+
+                Ok(serde_json::from_value(response)?)
+            }
+            Mode::Replay(exchanges) => {
+                let method = serde_json::to_value(method)?;
+                let params = serde_json::to_value(&params)?;
+
+                let response = exchanges
+                    .lock()
+                    .unwrap()
+                    .iter_mut()
+                    .find(|slot| {
+                        slot.as_ref().is_some_and(|exchange| {
+                            exchange.method == method && exchange.params == params
+                        })
+                    })
+                    .and_then(Option::take)
+                    .map(|exchange| exchange.response)
+                    .ok_or_else(|| TransportError::NotRecorded(method.to_string()))?;
+
+                Ok(serde_json::from_value(response)?)
+            }
+        }
+    }
+}
+
+fn response_to_json(response: JsonRpcResponse<Value>) -> Value {
+    match response {
+        JsonRpcResponse::Success { id, result } => {
+            json!({ "jsonrpc": "2.0", "id": id, "result": result })
+        }
+        JsonRpcResponse::Error { id, error } => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": error.code, "message": error.message },
+        }),
+    }
+}
@@ -1,14 +1,57 @@
-use crate::MovesRemaining;
+use crate::codec;
+use crate::error::DojoError;
+use crate::fees::format_amount;
+use crate::retry::RetryAttempted;
+use crate::transactions::Transactions;
+use crate::{
+    AccountInfo, ActiveAccount, ChainState, DojoConfig, DojoConnectionStatus, MovesRemaining,
+    Spectating,
+};
 use bevy::prelude::*;
 pub struct GameUI;
 
 #[derive(Component)]
 pub struct MovesRemainingText;
 
+#[derive(Component)]
+pub struct DojoErrorText;
+
+#[derive(Component)]
+pub struct PendingTransactionsText;
+
+#[derive(Component)]
+pub struct FeeEstimateText;
+
+#[derive(Component)]
+pub struct ConnectionStatusText;
+
+#[derive(Component)]
+pub struct ActiveAccountText;
+
+#[derive(Component)]
+pub struct AccountInfoText;
+
+#[derive(Component)]
+pub struct SpectatorText;
+
+// how long an error stays on screen
+const ERROR_DISPLAY_SECONDS: f32 = 5.0;
+
 impl Plugin for GameUI {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_game_ui)
-            .add_systems(Update, update_remaining_moves);
+        app.add_systems(Startup, spawn_game_ui).add_systems(
+            Update,
+            (
+                update_remaining_moves,
+                update_pending_transactions,
+                update_fee_estimate,
+                update_connection_status,
+                update_active_account,
+                update_account_info,
+                update_spectator,
+                show_dojo_errors,
+            ),
+        );
     }
 }
 
@@ -19,7 +62,8 @@ fn spawn_game_ui(mut commands: Commands) {
                 style: Style {
                     width: Val::Percent(100.0),
                     height: Val::Percent(20.0),
-                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
                     padding: UiRect::all(Val::Px(10.0)),
                     ..default()
                 },
@@ -29,6 +73,19 @@ fn spawn_game_ui(mut commands: Commands) {
             Name::new("UI Root"),
         ))
         .with_children(|commands| {
+            commands.spawn((
+                TextBundle {
+                    text: Text::from_section(
+                        "",
+                        TextStyle {
+                            font_size: 14.0,
+                            ..default()
+                        },
+                    ),
+                    ..default()
+                },
+                ConnectionStatusText,
+            ));
             commands.spawn((
                 TextBundle {
                     text: Text::from_section(
@@ -42,6 +99,104 @@ fn spawn_game_ui(mut commands: Commands) {
                 },
                 MovesRemainingText,
             ));
+            commands.spawn((
+                TextBundle {
+                    text: Text::from_section(
+                        "",
+                        TextStyle {
+                            font_size: 14.0,
+                            ..default()
+                        },
+                    ),
+                    ..default()
+                },
+                PendingTransactionsText,
+            ));
+            commands.spawn((
+                TextBundle {
+                    text: Text::from_section(
+                        "",
+                        TextStyle {
+                            font_size: 14.0,
+                            ..default()
+                        },
+                    ),
+                    ..default()
+                },
+                FeeEstimateText,
+            ));
+            commands.spawn((
+                TextBundle {
+                    text: Text::from_section(
+                        "",
+                        TextStyle {
+                            font_size: 14.0,
+                            color: Color::ORANGE_RED,
+                            ..default()
+                        },
+                    ),
+                    ..default()
+                },
+                DojoErrorText,
+            ));
+        });
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(10.0),
+                    right: Val::Px(10.0),
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(8.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                ..default()
+            },
+            Name::new("Account Panel"),
+        ))
+        .with_children(|commands| {
+            commands.spawn((
+                TextBundle {
+                    text: Text::from_section(
+                        "",
+                        TextStyle {
+                            font_size: 14.0,
+                            ..default()
+                        },
+                    ),
+                    ..default()
+                },
+                ActiveAccountText,
+            ));
+            commands.spawn((
+                TextBundle {
+                    text: Text::from_section(
+                        "Reading account...",
+                        TextStyle {
+                            font_size: 14.0,
+                            ..default()
+                        },
+                    ),
+                    ..default()
+                },
+                AccountInfoText,
+            ));
+            commands.spawn((
+                TextBundle {
+                    text: Text::from_section(
+                        "",
+                        TextStyle {
+                            font_size: 14.0,
+                            ..default()
+                        },
+                    ),
+                    ..default()
+                },
+                SpectatorText,
+            ));
         });
 }
 
@@ -53,3 +208,179 @@ fn update_remaining_moves(
         text.sections[0].value = format!("Moves remaining: {:?}", moves.0);
     }
 }
+
+fn update_pending_transactions(
+    mut texts: Query<&mut Text, With<PendingTransactionsText>>,
+    transactions: Res<Transactions>,
+) {
+    let pending = transactions.pending();
+    for mut text in &mut texts {
+        text.sections[0].value = match pending {
+            0 => String::new(),
+            1 => String::from("1 transaction pending"),
+            n => format!("{n} transactions pending"),
+        };
+    }
+}
+
+fn update_fee_estimate(
+    mut texts: Query<&mut Text, With<FeeEstimateText>>,
+    transactions: Res<Transactions>,
+) {
+    let Some(fee) = transactions.latest_fee() else {
+        return;
+    };
+
+    for mut text in &mut texts {
+        text.sections[0].value = format!(
+            "Estimated fee: {} (max {})",
+            format_amount(fee.estimate),
+            format_amount(fee.max_fee)
+        );
+    }
+}
+
+fn update_connection_status(
+    mut texts: Query<&mut Text, With<ConnectionStatusText>>,
+    status: Res<DojoConnectionStatus>,
+) {
+    if !status.is_changed() {
+        return;
+    }
+
+    let (value, color) = match &*status {
+        DojoConnectionStatus::Connecting => (String::from("Connecting..."), Color::GRAY),
+        DojoConnectionStatus::Connected { block_number } => (
+            format!("Connected, block {block_number}"),
+            Color::LIME_GREEN,
+        ),
+        DojoConnectionStatus::Degraded { reason } => (format!("Degraded: {reason}"), Color::GOLD),
+        DojoConnectionStatus::Disconnected { reason } => {
+            (format!("Disconnected: {reason}"), Color::ORANGE_RED)
+        }
+        DojoConnectionStatus::WrongChain { expected, actual } => (
+            format!("Wrong chain: expected {expected:#x}, node is on {actual:#x}"),
+            Color::ORANGE_RED,
+        ),
+    };
+
+    for mut text in &mut texts {
+        text.sections[0].value = value.clone();
+        text.sections[0].style.color = color;
+    }
+}
+
+fn update_active_account(
+    mut texts: Query<&mut Text, With<ActiveAccountText>>,
+    active_account: Res<ActiveAccount>,
+    config: Res<DojoConfig>,
+    spectating: Option<Res<Spectating>>,
+) {
+    if !active_account.is_changed() {
+        return;
+    }
+
+    let address = config.accounts[active_account.0].address;
+    let value = if let Some(spectating) = spectating {
+        format!("Spectating: {:#x}", spectating.player)
+    } else if config.accounts.len() > 1 {
+        format!(
+            "Account {}/{}: {:#x} (Tab to switch)",
+            active_account.0 + 1,
+            config.accounts.len(),
+            address
+        )
+    } else {
+        format!("Account: {:#x}", address)
+    };
+
+    for mut text in &mut texts {
+        text.sections[0].value = value.clone();
+    }
+}
+
+fn update_account_info(
+    mut texts: Query<&mut Text, With<AccountInfoText>>,
+    info: Option<Res<AccountInfo>>,
+) {
+    let Some(info) = info.filter(|info| info.is_changed()) else {
+        return;
+    };
+
+    let balance = match info.balance {
+        Some(balance) => format_amount(balance),
+        None => String::from("-"),
+    };
+    let value = format!("Nonce: {}\nFee-token balance: {}", info.nonce, balance);
+
+    for mut text in &mut texts {
+        text.sections[0].value = value.clone();
+    }
+}
+
+fn update_spectator(
+    mut texts: Query<&mut Text, With<SpectatorText>>,
+    spectating: Option<Res<Spectating>>,
+    chain_state: Res<ChainState>,
+) {
+    let Some(spectating) = spectating else {
+        return;
+    };
+    if !spectating.is_changed() && !chain_state.is_changed() {
+        return;
+    }
+
+    let state = match chain_state.game {
+        Some(game) if game.is_finished => "escaped",
+        Some(game) if game.is_initialised() && game.turns_remaining == 0 => "out of moves",
+        Some(game) if game.is_initialised() => "playing",
+        _ => "no game yet",
+    };
+    let inspected: Vec<String> = spectating
+        .inspected
+        .iter()
+        .map(|object_id| codec::decode_short(*object_id).unwrap_or(format!("{object_id:#x}")))
+        .collect();
+    let value = if inspected.is_empty() {
+        format!("Game: {state}")
+    } else {
+        format!("Game: {state}\nInspected: {}", inspected.join(", "))
+    };
+
+    for mut text in &mut texts {
+        text.sections[0].value = value.clone();
+    }
+}
+
+fn show_dojo_errors(
+    mut errors: EventReader<DojoError>,
+    mut retries: EventReader<RetryAttempted>,
+    mut texts: Query<&mut Text, With<DojoErrorText>>,
+    mut timer: Local<Option<Timer>>,
+    time: Res<Time>,
+) {
+    let retry = retries.read().last().map(|retry| {
+        format!(
+            "{}, retrying {} ({}/{})",
+            retry.error, retry.operation, retry.attempt, retry.max_retries
+        )
+    });
+    // a final failure replaces the retry notice
+    let message = errors.read().last().map(ToString::to_string).or(retry);
+
+    if let Some(message) = message {
+        for mut text in &mut texts {
+            text.sections[0].value = message.clone();
+        }
+        *timer = Some(Timer::from_seconds(ERROR_DISPLAY_SECONDS, TimerMode::Once));
+    }
+
+    if let Some(clear) = timer.as_mut() {
+        if clear.tick(time.delta()).just_finished() {
+            for mut text in &mut texts {
+                text.sections[0].value.clear();
+            }
+            *timer = None;
+        }
+    }
+}
@@ -0,0 +1,142 @@
+mod common;
+
+use async_compat::Compat;
+use common::{MockRpc, RpcError, BALANCE, TRANSACTION_HASH};
+use futures_lite::future;
+use game_demo::{
+    burner::{self, BurnerSettings},
+    configs::{BURNER_CLASS_HASH, FEE_TOKEN_ADDRESS},
+    dojo::DojoEnv,
+};
+use serde_json::json;
+use starknet::core::types::FieldElement;
+use std::path::Path;
+
+fn block_on<T>(task: impl std::future::Future<Output = T>) -> T {
+    future::block_on(Compat::new(task))
+}
+
+fn settings(name: &str) -> BurnerSettings {
+    BurnerSettings {
+        path: std::env::temp_dir().join(format!("{name}-{}.json", std::process::id())),
+        class_hash: FieldElement::from_hex_be(BURNER_CLASS_HASH).unwrap(),
+        funding: FieldElement::from(1000_u32),
+    }
+}
+
+fn remove(path: &Path) {
+    let _ = std::fs::remove_file(path);
+}
+
+// a devnet without the burner, which holds `burner_balance` of the fee token
+fn undeployed(burner_balance: &'static str) -> MockRpc {
+    let rpc = MockRpc::katana();
+    rpc.on("starknet_getClassHashAt", |_| {
+        Err(RpcError {
+            code: 20,
+            message: String::from("Contract not found"),
+        })
+    });
+    let account = FieldElement::from_hex_be(common::ACCOUNT_ADDRESS).unwrap();
+    rpc.on_call("balanceOf", move |calldata| {
+        let balance = if calldata[0] == account {
+            BALANCE
+        } else {
+            burner_balance
+        };
+        Ok(vec![
+            FieldElement::from_hex_be(balance).unwrap(),
+            FieldElement::ZERO,
+        ])
+    });
+    rpc.on("starknet_addDeployAccountTransaction", |_| {
+        Ok(json!({ "transaction_hash": TRANSACTION_HASH, "contract_address": "0x1" }))
+    });
+    rpc
+}
+
+#[test]
+fn new_burner_is_saved_funded_and_deployed() {
+    let rpc = undeployed("0x0");
+    let settings = settings("new-burner");
+    remove(&settings.path);
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    let keys = block_on(burner::load_or_create(&env, &settings)).unwrap();
+
+    // the funds go to the burner's address, through the fee token
+    let invokes = rpc.requests("starknet_addInvokeTransaction");
+    assert_eq!(invokes.len(), 1);
+    let invoke = invokes[0].to_string();
+    assert!(invoke.contains(&common::felt_hex(keys.address)));
+    assert!(invoke.contains(FEE_TOKEN_ADDRESS));
+    assert_eq!(
+        rpc.requests("starknet_addDeployAccountTransaction").len(),
+        1
+    );
+
+    // the next launch plays as the same account
+    let again = block_on(burner::load_or_create(&env, &settings)).unwrap();
+    assert_eq!(again, keys);
+
+    remove(&settings.path);
+}
+
+#[test]
+fn deployed_burner_is_reused() {
+    let rpc = MockRpc::katana();
+    rpc.on("starknet_getClassHashAt", |_| Ok(json!(BURNER_CLASS_HASH)));
+    let settings = settings("deployed-burner");
+    remove(&settings.path);
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    block_on(burner::load_or_create(&env, &settings)).unwrap();
+
+    assert!(settings.path.exists());
+    assert!(rpc.requests("starknet_addInvokeTransaction").is_empty());
+    assert!(rpc
+        .requests("starknet_addDeployAccountTransaction")
+        .is_empty());
+
+    remove(&settings.path);
+}
+
+#[test]
+fn partly_funded_burner_is_topped_up() {
+    // funded by a launch whose deployment failed
+    let rpc = undeployed("0x190");
+    let settings = settings("funded-burner");
+    remove(&settings.path);
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    block_on(burner::load_or_create(&env, &settings)).unwrap();
+
+    // 1000 - 400
+    let invokes = rpc.requests("starknet_addInvokeTransaction");
+    assert_eq!(invokes.len(), 1);
+    assert!(invokes[0].to_string().contains(r#""0x258""#));
+
+    remove(&settings.path);
+}
+
+#[test]
+fn unknown_block_is_not_an_undeployed_burner() {
+    let rpc = MockRpc::katana();
+    rpc.on("starknet_getClassHashAt", |_| {
+        Err(RpcError {
+            code: 24,
+            message: String::from("Block not found"),
+        })
+    });
+    let settings = settings("unknown-block-burner");
+    remove(&settings.path);
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    assert!(block_on(burner::load_or_create(&env, &settings)).is_err());
+    assert!(rpc.requests("starknet_addInvokeTransaction").is_empty());
+    assert!(rpc
+        .requests("starknet_addDeployAccountTransaction")
+        .is_empty());
+
+    remove(&settings.path);
+}
@@ -0,0 +1,69 @@
+use game_demo::codec::{self, CodecError};
+use starknet::core::types::FieldElement;
+
+const SENTENCE: &str = "A strange book, 1984, with a page torn out of the middle.";
+
+#[test]
+fn short_string_matches_cairo_literal() {
+    // 'Painting'
+    let felt = FieldElement::from_hex_be("0x5061696e74696e67").unwrap();
+
+    assert_eq!(codec::encode_short("Painting").unwrap(), felt);
+    assert_eq!(codec::decode_short(felt).unwrap(), "Painting");
+    assert_eq!(
+        codec::encode_short(SENTENCE).unwrap_err(),
+        CodecError::TooLong(SENTENCE.len())
+    );
+}
+
+#[test]
+fn felt_array_round_trips_and_reports_its_length() {
+    let mut felts = codec::encode_felt_array(SENTENCE);
+    assert_eq!(felts.len(), 3);
+
+    // whatever follows the array is left to the caller
+    felts.push(FieldElement::from(7_u32));
+    assert_eq!(
+        codec::decode_felt_array(&felts).unwrap(),
+        (SENTENCE.to_string(), 3)
+    );
+}
+
+#[test]
+fn truncated_layouts_are_rejected() {
+    let felts = codec::encode_felt_array(SENTENCE);
+    assert_eq!(
+        codec::decode_felt_array(&felts[..2]).unwrap_err(),
+        CodecError::Truncated("felt array")
+    );
+}
+
+#[test]
+fn unpack_matches_dojo_core_packing() {
+    let felt = |hex: &str| FieldElement::from_hex_be(hex).unwrap();
+    // felt252, u32, u256 as its two u128 halves, bool
+    let layout = [251_u32, 32, 128, 128, 1].map(FieldElement::from);
+    // the felt252 fills a felt, the high u128 no longer fits next to the u32 and the
+    // low u128
+    let packed = [
+        felt("0x123456789abcdef0123456789abcdef0123456789abcdef0123456789abcde"),
+        felt("0xffffffffffffffffffffffffffffffffdeadbeef"),
+        felt("0x100000000000000000000000000000001"),
+    ];
+
+    assert_eq!(codec::packed_size(&layout).unwrap(), packed.len());
+    assert_eq!(
+        codec::unpack(&packed, &layout).unwrap(),
+        vec![
+            felt("0x123456789abcdef0123456789abcdef0123456789abcdef0123456789abcde"),
+            felt("0xdeadbeef"),
+            felt("0xffffffffffffffffffffffffffffffff"),
+            FieldElement::ONE,
+            FieldElement::ONE,
+        ]
+    );
+    assert_eq!(
+        codec::unpack(&packed[..2], &layout).unwrap_err(),
+        CodecError::Truncated("packed entity")
+    );
+}
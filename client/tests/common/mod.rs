@@ -0,0 +1,515 @@
+//! Local stand-in for a Starknet JSON-RPC node, answering the calls made by
+//! `SingleOwnerAccount` and `WorldContractReader` from canned handlers.
+//!
+//! Contract calls are answered by entry point, the world's from the models declared
+//! with [`MockRpc::model`].
+
+#![allow(dead_code)]
+
+use game_demo::{
+    configs::{AccountKeys, DojoConfig, FEE_TOKEN_ADDRESS},
+    fees::FeePolicy,
+    retry::RetryPolicy,
+};
+use serde_json::{json, Value};
+use starknet::core::{
+    types::FieldElement,
+    utils::{cairo_short_string_to_felt, get_selector_from_name},
+};
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+use url::Url;
+
+pub const TRANSACTION_HASH: &str = "0x123";
+
+// katana account 0, the same defaults the client ships with
+pub const ACCOUNT_ADDRESS: &str =
+    "0x517ececd29116499f4a1b64b094da79ba08dfd54a3edaa316134c41f8160973";
+pub const ACCOUNT_SECRET_KEY: &str = "0x1800000000300000180000000000030000000000003006001800006600";
+pub const WORLD_ADDRESS: &str = "0x100";
+pub const ACTIONS_ADDRESS: &str = "0x200";
+pub const EXECUTOR_ADDRESS: &str = "0x300";
+// fee-token balance of the account, 1 ETH
+pub const BALANCE: &str = "0xde0b6b3a7640000";
+
+/// The JSON-RPC `error` object returned by a handler.
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+type Handler = Box<dyn Fn(&Value) -> Result<Value, RpcError> + Send>;
+
+type CallHandler = Box<dyn Fn(&[FieldElement]) -> Result<Vec<FieldElement>, RpcError> + Send>;
+
+/// A model member: its name, Cairo type and stored value.
+pub type Member = (&'static str, &'static str, FieldElement);
+
+// a model as the world and its class answer for it, with a single stored entity
+struct MockModel {
+    name: FieldElement,
+    class_hash: FieldElement,
+    schema: Vec<FieldElement>,
+    layout: Vec<FieldElement>,
+    unpacked_size: usize,
+    packed: Vec<FieldElement>,
+}
+
+#[derive(Default)]
+struct MockState {
+    handlers: HashMap<String, Handler>,
+    requests: Vec<(String, Value)>,
+}
+
+pub struct MockRpc {
+    url: Url,
+    state: Arc<Mutex<MockState>>,
+    // kept apart from `state`, which is locked while a handler runs
+    calls: Arc<Mutex<HashMap<FieldElement, CallHandler>>>,
+    models: Arc<Mutex<Vec<MockModel>>>,
+}
+
+impl MockRpc {
+    /// Listens on a free local port, every method answers with `-32601` until handled.
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+        let state = Arc::new(Mutex::new(MockState::default()));
+
+        let server_state = state.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { break };
+                let state = server_state.clone();
+                thread::spawn(move || serve(stream, &state));
+            }
+        });
+
+        Self {
+            url,
+            state,
+            calls: Arc::default(),
+            models: Arc::default(),
+        }
+    }
+
+    /// A node with the answers `task_interact` and `task_escape` need for a successful call.
+    pub fn katana() -> Self {
+        let rpc = Self::start();
+        rpc.on("starknet_chainId", |_| {
+            Ok(json!(felt_hex(
+                cairo_short_string_to_felt("KATANA").unwrap()
+            )))
+        });
+        rpc.on("starknet_blockNumber", |_| Ok(json!(1)));
+        rpc.on("starknet_getNonce", |_| Ok(json!("0x0")));
+        rpc.on("starknet_estimateFee", estimate_fee("0x1"));
+        rpc.route_calls();
+        rpc.on_call("balanceOf", |_| {
+            Ok(vec![
+                FieldElement::from_hex_be(BALANCE).unwrap(),
+                FieldElement::ZERO,
+            ])
+        });
+        rpc.serve_world();
+        rpc.on("starknet_addInvokeTransaction", |_| {
+            Ok(json!({ "transaction_hash": TRANSACTION_HASH }))
+        });
+        rpc.on("starknet_getTransactionReceipt", |_| {
+            Ok(invoke_receipt(None))
+        });
+        rpc
+    }
+
+    pub fn url(&self) -> Url {
+        self.url.clone()
+    }
+
+    /// Settings pointing the client at this node.
+    pub fn config(&self) -> DojoConfig {
+        DojoConfig {
+            json_rpc_endpoint: self.url(),
+            account_address: FieldElement::from_hex_be(ACCOUNT_ADDRESS).unwrap(),
+            account_secret_key: FieldElement::from_hex_be(ACCOUNT_SECRET_KEY).unwrap(),
+            world_address: FieldElement::from_hex_be(WORLD_ADDRESS).unwrap(),
+            actions_address: FieldElement::from_hex_be(ACTIONS_ADDRESS).unwrap(),
+            sync_interval: 1.0,
+            chain_id: cairo_short_string_to_felt("KATANA").unwrap(),
+            health_check_interval: 1.0,
+            rpc_record: None,
+            rpc_replay: None,
+            accounts: vec![AccountKeys {
+                address: FieldElement::from_hex_be(ACCOUNT_ADDRESS).unwrap(),
+                secret_key: FieldElement::from_hex_be(ACCOUNT_SECRET_KEY).unwrap(),
+            }],
+            // fast retries so failing tests do not wait on the backoff
+            retry: RetryPolicy {
+                max_retries: 2,
+                base_delay: Duration::from_millis(1),
+                max_delay: Duration::from_millis(10),
+            },
+            fee: FeePolicy {
+                multiplier: 1.5,
+                max_fee: None,
+                token: FieldElement::from_hex_be(FEE_TOKEN_ADDRESS).unwrap(),
+            },
+            burner: None,
+        }
+    }
+
+    /// Answers `method` with `handler`, replacing any previous handler.
+    pub fn on(
+        &self,
+        method: &str,
+        handler: impl Fn(&Value) -> Result<Value, RpcError> + Send + 'static,
+    ) {
+        self.state
+            .lock()
+            .unwrap()
+            .handlers
+            .insert(method.to_string(), Box::new(handler));
+    }
+
+    /// Answers `starknet_call`s to `entry_point` with `handler`, whatever the contract.
+    /// Takes effect on nodes made by [`katana`](Self::katana) only.
+    pub fn on_call(
+        &self,
+        entry_point: &str,
+        handler: impl Fn(&[FieldElement]) -> Result<Vec<FieldElement>, RpcError> + Send + 'static,
+    ) {
+        self.calls
+            .lock()
+            .unwrap()
+            .insert(selector(entry_point), Box::new(handler));
+    }
+
+    /// Declares the model `name` on the world, under a class hash of its own, replacing
+    /// any previous declaration like an upgrade would. `keys` and `values` are its
+    /// `#[key]` and other members in declaration order, their values are what the
+    /// world returns for any entity of the model.
+    pub fn model(&self, name: &str, keys: &[Member], values: &[Member]) {
+        let name = cairo_short_string_to_felt(name).unwrap();
+        let members = keys
+            .iter()
+            .map(|member| (member, true))
+            .chain(values.iter().map(|member| (member, false)));
+
+        // `Ty::Struct` with no attributes, then each member
+        let mut schema = vec![
+            FieldElement::ONE,
+            name,
+            FieldElement::ZERO,
+            FieldElement::from(keys.len() + values.len()),
+        ];
+        for (&(member, ty, _), key) in members {
+            let mut child = vec![short(member)];
+            if key {
+                child.extend([FieldElement::ONE, short("key")]);
+            } else {
+                child.push(FieldElement::ZERO);
+            }
+            // `Ty::Primitive`
+            child.extend([FieldElement::ZERO, short(ty)]);
+            schema.push(FieldElement::from(child.len()));
+            schema.extend(child);
+        }
+
+        // only values are stored, keys make up the storage address
+        let layout: Vec<_> = values
+            .iter()
+            .map(|&(_, ty, _)| FieldElement::from(bits(ty)))
+            .collect();
+        let packed = pack(values);
+
+        let mut models = self.models.lock().unwrap();
+        let class_hash = FieldElement::from(0x1000 + models.len());
+        models.retain(|model| model.name != name);
+        models.push(MockModel {
+            name,
+            class_hash,
+            schema,
+            layout,
+            unpacked_size: values.len(),
+            packed,
+        });
+    }
+
+    // `starknet_call` goes to the handler of its entry point
+    fn route_calls(&self) {
+        let calls = self.calls.clone();
+        self.on("starknet_call", move |params| {
+            let request = params.get("request").or_else(|| params.get(0));
+            let request = request.cloned().unwrap_or_default();
+            let selector = felt(&request["entry_point_selector"]);
+            let calldata: Vec<FieldElement> = request["calldata"]
+                .as_array()
+                .map(|calldata| calldata.iter().map(felt).collect())
+                .unwrap_or_default();
+
+            let calls = calls.lock().unwrap();
+            let handler = calls.get(&selector).ok_or_else(|| RpcError {
+                code: 40,
+                message: format!("Entry point {:#x} not found", selector),
+            })?;
+            let result = handler(&calldata)?;
+            Ok(json!(result.into_iter().map(felt_hex).collect::<Vec<_>>()))
+        });
+    }
+
+    // the world's `model`, `executor` and `entity`, and the executor's `call` into a
+    // model class
+    fn serve_world(&self) {
+        let models = self.models.clone();
+        self.on_call("model", move |calldata| {
+            let models = models.lock().unwrap();
+            let model = find(&models, |model| model.name == calldata[0])?;
+            Ok(vec![model.class_hash])
+        });
+
+        self.on_call("executor", |_| {
+            Ok(vec![FieldElement::from_hex_be(EXECUTOR_ADDRESS).unwrap()])
+        });
+
+        let models = self.models.clone();
+        self.on_call("call", move |calldata| {
+            let models = models.lock().unwrap();
+            let model = find(&models, |model| model.class_hash == calldata[0])?;
+            let result = match calldata[1] {
+                entry_point if entry_point == selector("schema") => model.schema.clone(),
+                entry_point if entry_point == selector("layout") => {
+                    span(model.layout.iter().copied())
+                }
+                entry_point if entry_point == selector("packed_size") => {
+                    vec![FieldElement::from(model.packed.len())]
+                }
+                entry_point if entry_point == selector("unpacked_size") => {
+                    vec![FieldElement::from(model.unpacked_size)]
+                }
+                entry_point if entry_point == selector("name") => vec![model.name],
+                entry_point => {
+                    return Err(RpcError {
+                        code: 40,
+                        message: format!("Entry point {:#x} not found", entry_point),
+                    })
+                }
+            };
+            Ok(span(result))
+        });
+
+        let models = self.models.clone();
+        self.on_call("entity", move |calldata| {
+            let models = models.lock().unwrap();
+            let model = find(&models, |model| model.name == calldata[0])?;
+            Ok(span(model.packed.iter().copied()))
+        });
+    }
+
+    /// Number of `starknet_call`s to `entry_point`, made directly or through the
+    /// world's executor.
+    pub fn calls(&self, entry_point: &str) -> usize {
+        let selector = felt_hex(selector(entry_point));
+        self.requests("starknet_call")
+            .iter()
+            .filter(|params| params.to_string().contains(&selector))
+            .count()
+    }
+
+    /// Params of every request received for `method`, in order.
+    pub fn requests(&self, method: &str) -> Vec<Value> {
+        self.state
+            .lock()
+            .unwrap()
+            .requests
+            .iter()
+            .filter(|(name, _)| name == method)
+            .map(|(_, params)| params.clone())
+            .collect()
+    }
+}
+
+/// A `starknet_estimateFee` handler estimating `overall_fee` for every transaction.
+pub fn estimate_fee(
+    overall_fee: &'static str,
+) -> impl Fn(&Value) -> Result<Value, RpcError> + Send + 'static {
+    move |params| {
+        // params are sent by name, or by position by older clients
+        let requests = params.get("request").or_else(|| params.get(0));
+        let count = requests.and_then(Value::as_array).map_or(1, Vec::len);
+        let estimate = json!({
+            "gas_consumed": overall_fee,
+            "gas_price": "0x1",
+            "overall_fee": overall_fee,
+        });
+        Ok(Value::Array(vec![estimate; count]))
+    }
+}
+
+/// An accepted invoke receipt, reverted with `revert_reason` when given.
+pub fn invoke_receipt(revert_reason: Option<&str>) -> Value {
+    let mut receipt = json!({
+        "type": "INVOKE",
+        "transaction_hash": TRANSACTION_HASH,
+        "actual_fee": "0x1",
+        "finality_status": "ACCEPTED_ON_L2",
+        "execution_status": "SUCCEEDED",
+        "block_hash": "0x1",
+        "block_number": 1,
+        "messages_sent": [],
+        "events": [],
+    });
+    if let Some(reason) = revert_reason {
+        receipt["execution_status"] = json!("REVERTED");
+        receipt["revert_reason"] = json!(reason);
+    }
+    receipt
+}
+
+/// An event emitted through the world, keyed by its selector like `emit!` does.
+pub fn world_event(name: &str, data: &[FieldElement]) -> Value {
+    let data: Vec<String> = data.iter().copied().map(felt_hex).collect();
+    json!({
+        "from_address": WORLD_ADDRESS,
+        "keys": [felt_hex(get_selector_from_name(name).unwrap())],
+        "data": data,
+    })
+}
+
+pub fn felt_hex(felt: FieldElement) -> String {
+    format!("{:#x}", felt)
+}
+
+fn felt(value: &Value) -> FieldElement {
+    FieldElement::from_hex_be(value.as_str().unwrap_or_default()).unwrap_or_default()
+}
+
+fn short(string: &str) -> FieldElement {
+    cairo_short_string_to_felt(string).unwrap()
+}
+
+fn selector(name: &str) -> FieldElement {
+    get_selector_from_name(name).unwrap()
+}
+
+// a Cairo `Span<felt252>`, prefixed with its length
+fn span(values: impl IntoIterator<Item = FieldElement>) -> Vec<FieldElement> {
+    let values: Vec<_> = values.into_iter().collect();
+    std::iter::once(FieldElement::from(values.len()))
+        .chain(values)
+        .collect()
+}
+
+fn find<'a>(
+    models: &'a [MockModel],
+    matches: impl Fn(&MockModel) -> bool,
+) -> Result<&'a MockModel, RpcError> {
+    models.iter().find(|model| matches(model)).ok_or(RpcError {
+        code: 40,
+        message: String::from("Contract error"),
+    })
+}
+
+// bits taken by a value of the Cairo type `ty` once packed
+fn bits(ty: &str) -> u32 {
+    match ty {
+        "bool" => 1,
+        "u8" => 8,
+        "u16" => 16,
+        "u32" => 32,
+        "u64" => 64,
+        "u128" => 128,
+        _ => 251,
+    }
+}
+
+// packs `values` like dojo-core does, from the lowest bit of each felt, starting a new
+// felt when the next value does not fit in the current one
+fn pack(values: &[Member]) -> Vec<FieldElement> {
+    let mut packed = Vec::new();
+    let mut current = FieldElement::ZERO;
+    let mut offset = 0;
+
+    for &(_, ty, value) in values {
+        let size = bits(ty);
+        if offset + size > 251 {
+            packed.push(current);
+            current = FieldElement::ZERO;
+            offset = 0;
+        }
+        let shift = (0..offset).fold(FieldElement::ONE, |shift, _| shift + shift);
+        current = current + value * shift;
+        offset += size;
+    }
+    if offset > 0 {
+        packed.push(current);
+    }
+    packed
+}
+
+// one request per connection, answered with `Connection: close`
+fn serve(stream: TcpStream, state: &Mutex<MockState>) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut content_length = 0;
+
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).unwrap_or(0) == 0 {
+            return;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    if reader.read_exact(&mut body).is_err() {
+        return;
+    }
+    let request: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    let response = respond(&request, state).to_string();
+
+    let mut stream = stream;
+    let _ = write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.len(),
+        response
+    );
+}
+
+fn respond(request: &Value, state: &Mutex<MockState>) -> Value {
+    let id = request["id"].clone();
+    let method = request["method"].as_str().unwrap_or_default().to_string();
+    let params = request["params"].clone();
+
+    let mut state = state.lock().unwrap();
+    state.requests.push((method.clone(), params.clone()));
+
+    let result = match state.handlers.get(&method) {
+        Some(handler) => handler(&params),
+        None => Err(RpcError {
+            code: -32601,
+            message: format!("method {method} not found"),
+        }),
+    };
+
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(e) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": e.code, "message": e.message },
+        }),
+    }
+}
@@ -0,0 +1,121 @@
+use game_demo::configs::{ConfigError, DojoConfig};
+use starknet::core::types::FieldElement;
+use std::{
+    env, fs,
+    path::PathBuf,
+    sync::{Mutex, PoisonError},
+};
+
+// the settings are read from the environment, which every test of this file shares
+static ENV: Mutex<()> = Mutex::new(());
+
+// secret key in `fixtures/keystore.json`, encrypted with `PASSWORD`
+const KEYSTORE_SECRET_KEY: &str = "0x2d8b0e0f4a9a1c6e8b17d3e5f00c0ffee";
+const PASSWORD: &str = "correct horse battery staple";
+
+fn keystore() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/keystore.json")
+}
+
+// loads `settings` as the settings file, with `password` in `DOJO_KEYSTORE_PASSWORD`
+fn load(name: &str, settings: &str, password: Option<&str>) -> Result<DojoConfig, ConfigError> {
+    let _env = ENV.lock().unwrap_or_else(PoisonError::into_inner);
+    let path = env::temp_dir().join(format!("{name}-{}.toml", std::process::id()));
+    fs::write(&path, settings).unwrap();
+
+    env::set_var("DOJO_CONFIG", &path);
+    for var in ["DOJO_PRIVATE_KEY", "DOJO_KEYSTORE", "DOJO_DEV_KEYS"] {
+        env::remove_var(var);
+    }
+    match password {
+        Some(password) => env::set_var("DOJO_KEYSTORE_PASSWORD", password),
+        None => env::remove_var("DOJO_KEYSTORE_PASSWORD"),
+    }
+
+    let config = DojoConfig::load();
+    let _ = fs::remove_file(&path);
+    config
+}
+
+#[test]
+fn plaintext_key_is_refused_without_the_dev_flag() {
+    let config = load("plaintext-key", "account_secret_key = \"0x1\"\n", None);
+
+    assert!(matches!(
+        config,
+        Err(ConfigError::NoKeystore(
+            "account_secret_key",
+            "keystore_path"
+        ))
+    ));
+}
+
+#[test]
+fn plaintext_key_is_accepted_with_the_dev_flag() {
+    let settings = "dev_plaintext_keys = true\naccount_secret_key = \"0x1\"\n";
+
+    let config = load("dev-plaintext-key", settings, None).unwrap();
+
+    assert_eq!(config.account_secret_key, FieldElement::ONE);
+}
+
+#[test]
+fn dev_flag_does_not_provide_a_key() {
+    let config = load("dev-no-key", "dev_plaintext_keys = true\n", None);
+
+    assert!(matches!(
+        config,
+        Err(ConfigError::NoKey("account_secret_key", "keystore_path"))
+    ));
+}
+
+#[test]
+fn plaintext_key_and_keystore_conflict() {
+    let settings = format!(
+        "dev_plaintext_keys = true\naccount_secret_key = \"0x1\"\nkeystore_path = {:?}\n",
+        keystore()
+    );
+
+    let config = load("key-conflict", &settings, Some(PASSWORD));
+
+    assert!(matches!(
+        config,
+        Err(ConfigError::Conflict("account_secret_key", "keystore_path"))
+    ));
+}
+
+#[test]
+fn burner_is_not_recorded() {
+    let settings = concat!(
+        "dev_plaintext_keys = true\naccount_secret_key = \"0x1\"\n",
+        "burner = true\nrpc_record = \"session.jsonl\"\n",
+    );
+
+    let config = load("burner-record", settings, None);
+
+    assert!(matches!(
+        config,
+        Err(ConfigError::Conflict("burner", "rpc_record"))
+    ));
+}
+
+#[test]
+fn keystore_is_unlocked_with_the_password_from_the_environment() {
+    let settings = format!("keystore_path = {:?}\n", keystore());
+
+    let config = load("keystore", &settings, Some(PASSWORD)).unwrap();
+
+    assert_eq!(
+        config.account_secret_key,
+        FieldElement::from_hex_be(KEYSTORE_SECRET_KEY).unwrap()
+    );
+}
+
+#[test]
+fn keystore_with_the_wrong_password_is_an_error() {
+    let settings = format!("keystore_path = {:?}\n", keystore());
+
+    let config = load("keystore-wrong-password", &settings, Some("hunter2"));
+
+    assert!(matches!(config, Err(ConfigError::Keystore(path, _)) if path == keystore()));
+}
@@ -0,0 +1,713 @@
+mod common;
+
+use async_compat::Compat;
+use common::{
+    estimate_fee, invoke_receipt, world_event, MockRpc, RpcError, ACCOUNT_ADDRESS,
+    TRANSACTION_HASH, WORLD_ADDRESS,
+};
+use futures_lite::future;
+use game_demo::{
+    backend::GameBackend,
+    codec,
+    configs::{AccountKeys, DojoConfig},
+    dojo::DojoEnv,
+    error::DojoErrorKind,
+    events::{ContractEvent, GameState},
+    fees::{FeePolicy, FeeQuote},
+    models::{Game, Object},
+    resources::DojoConnectionStatus,
+    transactions::TransactionStatus,
+    transport::TransportError,
+};
+use serde_json::json;
+use starknet::core::{
+    types::FieldElement,
+    utils::{cairo_short_string_to_felt, get_selector_from_name},
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const SENTENCE: &str = "A strange book, 1984, with a page torn out of the middle.";
+
+fn block_on<T>(task: impl std::future::Future<Output = T>) -> T {
+    future::block_on(Compat::new(task))
+}
+
+#[test]
+fn interact_sends_invoke_and_waits_for_receipt() {
+    let rpc = MockRpc::katana();
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+    let object_id = cairo_short_string_to_felt("Bookcase").unwrap();
+
+    block_on(env.interact(object_id)).unwrap();
+
+    let invokes = rpc.requests("starknet_addInvokeTransaction");
+    assert_eq!(invokes.len(), 1);
+    assert!(invokes[0]
+        .to_string()
+        .contains(&common::felt_hex(object_id)));
+
+    let receipts = rpc.requests("starknet_getTransactionReceipt");
+    assert!(receipts[0].to_string().contains(TRANSACTION_HASH));
+}
+
+#[test]
+fn escape_waits_until_the_receipt_is_known() {
+    let rpc = MockRpc::katana();
+    let polls = AtomicUsize::new(0);
+    rpc.on("starknet_getTransactionReceipt", move |_| {
+        if polls.fetch_add(1, Ordering::SeqCst) < 2 {
+            return Err(RpcError {
+                code: 29,
+                message: String::from("Transaction hash not found"),
+            });
+        }
+        Ok(invoke_receipt(None))
+    });
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    block_on(env.escape(cairo_short_string_to_felt("1984").unwrap())).unwrap();
+
+    assert_eq!(rpc.requests("starknet_getTransactionReceipt").len(), 3);
+}
+
+#[test]
+fn escape_reports_the_revert_reason() {
+    let rpc = MockRpc::katana();
+    rpc.on("starknet_getTransactionReceipt", |_| {
+        Ok(invoke_receipt(Some("Error in the called contract")))
+    });
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    let error = block_on(env.escape(cairo_short_string_to_felt("1984").unwrap())).unwrap_err();
+
+    assert_eq!(error.kind, DojoErrorKind::Revert);
+    assert!(error.message.contains("Error in the called contract"));
+}
+
+#[test]
+fn rejected_execution_is_a_revert() {
+    let rpc = MockRpc::katana();
+    rpc.on("starknet_estimateFee", |_| {
+        Err(RpcError {
+            code: 40,
+            message: String::from("Contract error"),
+        })
+    });
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    let error = block_on(env.interact(cairo_short_string_to_felt("Door").unwrap())).unwrap_err();
+
+    assert_eq!(error.kind, DojoErrorKind::Revert);
+    assert!(rpc.requests("starknet_addInvokeTransaction").is_empty());
+    // reverts are not retried
+    assert_eq!(rpc.requests("starknet_estimateFee").len(), 1);
+    assert!(env.take_retries().is_empty());
+}
+
+#[test]
+fn model_read_failure_is_a_network_error() {
+    let rpc = MockRpc::katana();
+    rpc.on("starknet_call", |_| {
+        Err(RpcError {
+            code: 20,
+            message: String::from("Contract not found"),
+        })
+    });
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    let error = block_on(env.game()).unwrap_err();
+
+    assert_eq!(error.kind, DojoErrorKind::Network);
+    let calls = rpc.requests("starknet_call");
+    assert!(!calls.is_empty());
+    assert!(calls[0].to_string().contains(WORLD_ADDRESS));
+}
+
+#[test]
+fn game_is_read_from_the_world() {
+    let rpc = MockRpc::katana();
+    let player = FieldElement::from_hex_be(ACCOUNT_ADDRESS).unwrap();
+    rpc.model(
+        "Game",
+        &[("player", "ContractAddress", player)],
+        &[
+            ("game_id", "u32", FieldElement::from(3_u32)),
+            ("start_time", "u64", FieldElement::from(1_700_000_000_u64)),
+            ("turns_remaining", "u64", FieldElement::from(10_u32)),
+            ("is_finished", "bool", FieldElement::ONE),
+        ],
+    );
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    let game = block_on(env.game()).unwrap();
+
+    assert_eq!(
+        game,
+        Game {
+            player,
+            game_id: 3,
+            start_time: 1_700_000_000,
+            turns_remaining: 10,
+            is_finished: true,
+        }
+    );
+    assert!(rpc.calls("schema") > 0);
+}
+
+#[test]
+fn object_is_read_from_the_world() {
+    let rpc = MockRpc::katana();
+    let player = FieldElement::from_hex_be(ACCOUNT_ADDRESS).unwrap();
+    let object_id = cairo_short_string_to_felt("Bookcase").unwrap();
+    rpc.model(
+        "Object",
+        &[
+            ("player", "ContractAddress", player),
+            ("object_id", "felt252", object_id),
+        ],
+        &[
+            ("game_id", "u32", FieldElement::from(3_u32)),
+            ("description_len", "u32", FieldElement::from(2_u32)),
+        ],
+    );
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    let object = block_on(env.object(object_id)).unwrap();
+
+    assert_eq!(
+        object,
+        Object {
+            player,
+            object_id,
+            game_id: 3,
+            description_len: 2,
+        }
+    );
+    // the entity is looked up by the player and the object
+    let entity = common::felt_hex(get_selector_from_name("entity").unwrap());
+    let calls = rpc.requests("starknet_call");
+    let read = calls
+        .iter()
+        .map(ToString::to_string)
+        .find(|call| call.contains(&entity))
+        .unwrap();
+    assert!(read.contains(ACCOUNT_ADDRESS));
+    assert!(read.contains(&common::felt_hex(object_id)));
+}
+
+#[test]
+fn replayed_session_needs_no_node() {
+    let recording = std::env::temp_dir().join(format!("dojo-session-{}.jsonl", std::process::id()));
+    let object_id = cairo_short_string_to_felt("Bookcase").unwrap();
+    // left over by an earlier run that failed
+    let _ = std::fs::remove_file(&recording);
+
+    let rpc = MockRpc::katana();
+    let env = DojoEnv::from_config(DojoConfig {
+        rpc_record: Some(recording.clone()),
+        ..rpc.config()
+    })
+    .unwrap();
+    block_on(env.interact(object_id)).unwrap();
+
+    // nothing answers on this node, every call has to come from the recording
+    let offline = MockRpc::start();
+    let env = DojoEnv::from_config(DojoConfig {
+        rpc_replay: Some(recording.clone()),
+        ..offline.config()
+    })
+    .unwrap();
+    block_on(env.interact(object_id)).unwrap();
+
+    // the recording holds a single invoke
+    let error = block_on(env.interact(object_id)).unwrap_err();
+    assert_eq!(error.kind, DojoErrorKind::Network);
+    assert!(offline.requests("starknet_addInvokeTransaction").is_empty());
+
+    std::fs::remove_file(recording).unwrap();
+}
+
+#[test]
+fn recording_does_not_overwrite_an_earlier_session() {
+    let recording = std::env::temp_dir().join(format!("dojo-kept-{}.jsonl", std::process::id()));
+    std::fs::write(&recording, "earlier session\n").unwrap();
+
+    let rpc = MockRpc::katana();
+    let error = DojoEnv::from_config(DojoConfig {
+        rpc_record: Some(recording.clone()),
+        ..rpc.config()
+    })
+    .err();
+
+    assert!(matches!(error, Some(TransportError::RecordingExists(path)) if path == recording));
+    assert_eq!(
+        std::fs::read_to_string(&recording).unwrap(),
+        "earlier session\n"
+    );
+
+    std::fs::remove_file(recording).unwrap();
+}
+
+#[test]
+fn concurrent_actions_use_consecutive_nonces() {
+    let rpc = MockRpc::katana();
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+    let bookcase = cairo_short_string_to_felt("Bookcase").unwrap();
+    let painting = cairo_short_string_to_felt("Painting").unwrap();
+
+    let (first, second) = block_on(future::zip(env.interact(bookcase), env.interact(painting)));
+    first.unwrap();
+    second.unwrap();
+
+    assert_eq!(rpc.requests("starknet_getNonce").len(), 1);
+    let invokes = rpc.requests("starknet_addInvokeTransaction");
+    assert_eq!(invokes.len(), 2);
+    assert!(invokes[0].to_string().contains(r#""nonce":"0x0""#));
+    assert!(invokes[1].to_string().contains(r#""nonce":"0x1""#));
+    assert!(env
+        .transactions()
+        .iter()
+        .all(|transaction| transaction.status == TransactionStatus::Confirmed));
+}
+
+#[test]
+fn next_action_waits_for_the_retries_of_a_lost_send() {
+    let rpc = MockRpc::katana();
+    let attempts = AtomicUsize::new(0);
+    rpc.on("starknet_addInvokeTransaction", move |_| {
+        if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+            return Err(RpcError {
+                code: -32603,
+                message: String::from("Internal error"),
+            });
+        }
+        Ok(json!({ "transaction_hash": TRANSACTION_HASH }))
+    });
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+    let bookcase = cairo_short_string_to_felt("Bookcase").unwrap();
+    let painting = cairo_short_string_to_felt("Painting").unwrap();
+
+    let (first, second) = block_on(future::zip(env.interact(bookcase), env.interact(painting)));
+    first.unwrap();
+    second.unwrap();
+
+    // the copy of the lost send goes out before the next action takes a nonce
+    let invokes = rpc.requests("starknet_addInvokeTransaction");
+    assert_eq!(invokes.len(), 3);
+    assert_eq!(invokes[0], invokes[1]);
+    assert!(invokes[1].to_string().contains(&common::felt_hex(bookcase)));
+    assert!(invokes[2].to_string().contains(&common::felt_hex(painting)));
+    assert!(invokes[2].to_string().contains(r#""nonce":"0x1""#));
+    assert_eq!(rpc.requests("starknet_getNonce").len(), 1);
+}
+
+#[test]
+fn stale_nonce_is_read_again() {
+    let rpc = MockRpc::katana();
+    let attempts = AtomicUsize::new(0);
+    rpc.on("starknet_addInvokeTransaction", move |_| {
+        if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+            return Err(RpcError {
+                code: 52,
+                message: String::from("Invalid transaction nonce"),
+            });
+        }
+        Ok(json!({ "transaction_hash": TRANSACTION_HASH }))
+    });
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    block_on(env.interact(cairo_short_string_to_felt("Door").unwrap())).unwrap();
+
+    assert_eq!(rpc.requests("starknet_getNonce").len(), 2);
+    assert_eq!(rpc.requests("starknet_addInvokeTransaction").len(), 2);
+}
+
+#[test]
+fn network_failure_is_retried() {
+    let rpc = MockRpc::katana();
+    let failures = AtomicUsize::new(0);
+    rpc.on("starknet_getNonce", move |_| {
+        if failures.fetch_add(1, Ordering::SeqCst) == 0 {
+            return Err(RpcError {
+                code: -32603,
+                message: String::from("Internal error"),
+            });
+        }
+        Ok(json!("0x0"))
+    });
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    block_on(env.interact(cairo_short_string_to_felt("Door").unwrap())).unwrap();
+
+    let retries = env.take_retries();
+    assert_eq!(retries.len(), 1);
+    assert_eq!(retries[0].operation, "interact");
+    assert_eq!(retries[0].attempt, 1);
+    assert_eq!(rpc.requests("starknet_addInvokeTransaction").len(), 1);
+}
+
+#[test]
+fn send_lost_in_transport_goes_out_again_unchanged() {
+    let rpc = MockRpc::katana();
+    let attempts = AtomicUsize::new(0);
+    rpc.on("starknet_addInvokeTransaction", move |_| {
+        if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+            return Err(RpcError {
+                code: -32603,
+                message: String::from("Internal error"),
+            });
+        }
+        Ok(json!({ "transaction_hash": TRANSACTION_HASH }))
+    });
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    block_on(env.interact(cairo_short_string_to_felt("Door").unwrap())).unwrap();
+
+    // same nonce and max fee, so the node can refuse the copy of a received send
+    let invokes = rpc.requests("starknet_addInvokeTransaction");
+    assert_eq!(invokes.len(), 2);
+    assert_eq!(invokes[0], invokes[1]);
+    assert_eq!(rpc.requests("starknet_getNonce").len(), 1);
+    assert_eq!(rpc.requests("starknet_estimateFee").len(), 1);
+    assert_eq!(env.take_retries().len(), 1);
+}
+
+#[test]
+fn duplicate_of_a_lost_send_waits_for_the_first() {
+    let rpc = MockRpc::katana();
+    let attempts = AtomicUsize::new(0);
+    rpc.on("starknet_addInvokeTransaction", move |_| {
+        let (code, message) = match attempts.fetch_add(1, Ordering::SeqCst) {
+            0 => (-32603, "Internal error"),
+            _ => (
+                59,
+                "A transaction with the same hash already exists in the mempool",
+            ),
+        };
+        Err(RpcError {
+            code,
+            message: String::from(message),
+        })
+    });
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    block_on(env.interact(cairo_short_string_to_felt("Door").unwrap())).unwrap();
+
+    assert_eq!(rpc.requests("starknet_addInvokeTransaction").len(), 2);
+    assert_eq!(rpc.requests("starknet_getNonce").len(), 1);
+    assert_eq!(env.transactions()[0].status, TransactionStatus::Confirmed);
+}
+
+#[test]
+fn lost_send_whose_nonce_was_taken_is_signed_again() {
+    let rpc = MockRpc::katana();
+    // the first send never reaches the node, another client then uses its nonce
+    let attempts = AtomicUsize::new(0);
+    rpc.on("starknet_addInvokeTransaction", move |_| {
+        let (code, message) = match attempts.fetch_add(1, Ordering::SeqCst) {
+            0 => (-32603, "Internal error"),
+            1 => (52, "Invalid transaction nonce"),
+            _ => return Ok(json!({ "transaction_hash": TRANSACTION_HASH })),
+        };
+        Err(RpcError {
+            code,
+            message: String::from(message),
+        })
+    });
+    let reads = AtomicUsize::new(0);
+    rpc.on("starknet_getNonce", move |_| {
+        let nonce = reads.fetch_add(1, Ordering::SeqCst);
+        Ok(json!(format!("{nonce:#x}")))
+    });
+    // only the sends the node took have a receipt
+    rpc.on("starknet_getTransactionReceipt", |params| {
+        let hash = params.get("transaction_hash").or_else(|| params.get(0));
+        if hash.and_then(|hash| hash.as_str()) == Some(TRANSACTION_HASH) {
+            return Ok(invoke_receipt(None));
+        }
+        Err(RpcError {
+            code: 29,
+            message: String::from("Transaction hash not found"),
+        })
+    });
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+    let bookcase = cairo_short_string_to_felt("Bookcase").unwrap();
+    let painting = cairo_short_string_to_felt("Painting").unwrap();
+
+    let (first, second) = block_on(future::zip(env.interact(bookcase), env.interact(painting)));
+    first.unwrap();
+    second.unwrap();
+
+    let invokes = rpc.requests("starknet_addInvokeTransaction");
+    assert_eq!(invokes.len(), 4);
+    assert_eq!(invokes[0], invokes[1]);
+    let resigned = invokes[2].to_string();
+    assert!(resigned.contains(&common::felt_hex(bookcase)));
+    assert!(resigned.contains(r#""nonce":"0x1""#));
+    let next = invokes[3].to_string();
+    assert!(next.contains(&common::felt_hex(painting)));
+    assert!(next.contains(r#""nonce":"0x2""#));
+    assert_eq!(rpc.requests("starknet_getNonce").len(), 2);
+    assert!(env
+        .transactions()
+        .iter()
+        .all(|transaction| transaction.status == TransactionStatus::Confirmed));
+}
+
+#[test]
+fn rejected_send_is_not_retried() {
+    let rpc = MockRpc::katana();
+    rpc.on("starknet_addInvokeTransaction", |_| {
+        Err(RpcError {
+            // not a stale nonce, even though the message says nonce
+            code: 55,
+            message: String::from("Account validation failed: bad nonce signature"),
+        })
+    });
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    let error = block_on(env.interact(cairo_short_string_to_felt("Door").unwrap())).unwrap_err();
+
+    assert_eq!(error.kind, DojoErrorKind::Revert);
+    assert_eq!(rpc.requests("starknet_addInvokeTransaction").len(), 1);
+    assert_eq!(rpc.requests("starknet_getNonce").len(), 1);
+    assert!(env.take_retries().is_empty());
+}
+
+#[test]
+fn escape_returns_the_events_of_the_receipt() {
+    let rpc = MockRpc::katana();
+    rpc.on("starknet_getTransactionReceipt", |_| {
+        let mut receipt = invoke_receipt(None);
+        let mut foreign = world_event("GameState", &[GameState::Escaped.to_felt()]);
+        foreign["from_address"] = json!("0x999");
+        receipt["events"] = json!([
+            // written by the world itself, not by `actions`
+            world_event("StoreSetRecord", &[]),
+            foreign,
+            world_event("GameState", &[GameState::WrongSecret.to_felt()]),
+        ]);
+        Ok(receipt)
+    });
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    let events = block_on(env.escape(cairo_short_string_to_felt("1234").unwrap())).unwrap();
+
+    assert_eq!(
+        events,
+        vec![ContractEvent::GameState(GameState::WrongSecret)]
+    );
+}
+
+#[test]
+fn interact_decodes_object_data() {
+    let rpc = MockRpc::katana();
+    let object_id = cairo_short_string_to_felt("Bookcase").unwrap();
+    rpc.on("starknet_getTransactionReceipt", move |_| {
+        let mut data = vec![object_id];
+        data.extend(codec::encode_felt_array(SENTENCE));
+        let mut receipt = invoke_receipt(None);
+        receipt["events"] = json!([
+            world_event("GameState", &[GameState::CheckingItem.to_felt()]),
+            world_event("ObjectData", &data),
+        ]);
+        Ok(receipt)
+    });
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    let events = block_on(env.interact(object_id)).unwrap();
+
+    assert_eq!(
+        events,
+        vec![
+            ContractEvent::GameState(GameState::CheckingItem),
+            ContractEvent::ObjectData {
+                object_id,
+                description: SENTENCE.to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn spawn_object_sends_descriptions_as_short_string_arrays() {
+    let rpc = MockRpc::katana();
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+    let bookcase = cairo_short_string_to_felt("Bookcase").unwrap();
+    let door = cairo_short_string_to_felt("Door").unwrap();
+
+    block_on(env.spawn_object(
+        vec![bookcase, door],
+        vec![SENTENCE.to_string(), String::from("Needs a key")],
+    ))
+    .unwrap();
+
+    // ids, then `[len, ...words]` for each description: 3 + 2 felts
+    let mut expected = vec![FieldElement::TWO, bookcase, door, FieldElement::from(5_u32)];
+    expected.extend(codec::encode_felt_array(SENTENCE));
+    expected.extend(codec::encode_felt_array("Needs a key"));
+    let expected: Vec<String> = expected.into_iter().map(common::felt_hex).collect();
+
+    let invokes = rpc.requests("starknet_addInvokeTransaction");
+    assert!(invokes[0].to_string().contains(
+        &json!(expected)
+            .to_string()
+            .trim_matches(|c| c == '[' || c == ']')
+    ));
+}
+
+#[test]
+fn health_reports_the_latest_block() {
+    let rpc = MockRpc::katana();
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    assert_eq!(
+        block_on(env.health()),
+        DojoConnectionStatus::Connected { block_number: 1 }
+    );
+}
+
+#[test]
+fn health_detects_another_chain() {
+    let rpc = MockRpc::katana();
+    let actual = cairo_short_string_to_felt("SN_GOERLI").unwrap();
+    rpc.on("starknet_chainId", move |_| {
+        Ok(json!(common::felt_hex(actual)))
+    });
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    assert_eq!(
+        block_on(env.health()),
+        DojoConnectionStatus::WrongChain {
+            expected: cairo_short_string_to_felt("KATANA").unwrap(),
+            actual,
+        }
+    );
+}
+
+#[test]
+fn health_without_block_number_is_degraded() {
+    let rpc = MockRpc::katana();
+    rpc.on("starknet_blockNumber", |_| {
+        Err(RpcError {
+            code: 32,
+            message: String::from("No blocks"),
+        })
+    });
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    assert!(matches!(
+        block_on(env.health()),
+        DojoConnectionStatus::Degraded { .. }
+    ));
+}
+
+#[test]
+fn health_without_answer_is_disconnected() {
+    // every method is unknown to a bare node
+    let rpc = MockRpc::start();
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    assert!(matches!(
+        block_on(env.health()),
+        DojoConnectionStatus::Disconnected { .. }
+    ));
+}
+
+#[test]
+fn switched_account_signs_the_next_actions() {
+    let rpc = MockRpc::katana();
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+    let second = AccountKeys {
+        address: FieldElement::from_hex_be("0x1234").unwrap(),
+        secret_key: FieldElement::from_hex_be("0x2").unwrap(),
+    };
+
+    block_on(env.interact(cairo_short_string_to_felt("Door").unwrap())).unwrap();
+    block_on(env.switch_account(second)).unwrap();
+    block_on(env.interact(cairo_short_string_to_felt("Door").unwrap())).unwrap();
+
+    // the nonce is read again for the new account
+    let nonces = rpc.requests("starknet_getNonce");
+    assert_eq!(nonces.len(), 2);
+    assert!(nonces[1].to_string().contains("0x1234"));
+    let invokes = rpc.requests("starknet_addInvokeTransaction");
+    assert!(invokes[1].to_string().contains("0x1234"));
+}
+
+#[test]
+fn max_fee_is_the_estimate_times_the_multiplier() {
+    let rpc = MockRpc::katana();
+    rpc.on("starknet_estimateFee", estimate_fee("0x64"));
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    block_on(env.interact(cairo_short_string_to_felt("Door").unwrap())).unwrap();
+
+    let invokes = rpc.requests("starknet_addInvokeTransaction");
+    assert!(invokes[0].to_string().contains(r#""max_fee":"0x96""#));
+    assert_eq!(
+        env.transactions()[0].fee,
+        Some(FeeQuote {
+            estimate: FieldElement::from(100_u32),
+            max_fee: FieldElement::from(150_u32),
+        })
+    );
+}
+
+#[test]
+fn fee_above_the_cap_is_refused() {
+    let rpc = MockRpc::katana();
+    rpc.on("starknet_estimateFee", estimate_fee("0x64"));
+    let config = rpc.config();
+    let env = DojoEnv::from_config(DojoConfig {
+        fee: FeePolicy {
+            max_fee: Some(FieldElement::from(80_u32)),
+            ..config.fee
+        },
+        ..config
+    })
+    .unwrap();
+
+    let error = block_on(env.escape(cairo_short_string_to_felt("1984").unwrap())).unwrap_err();
+
+    assert_eq!(error.kind, DojoErrorKind::Fee);
+    assert!(rpc.requests("starknet_addInvokeTransaction").is_empty());
+    assert!(env.take_retries().is_empty());
+}
+
+#[test]
+fn balance_below_the_max_fee_is_refused() {
+    let rpc = MockRpc::katana();
+    rpc.on("starknet_estimateFee", estimate_fee("0x64"));
+    rpc.on_call("balanceOf", |_| {
+        Ok(vec![FieldElement::from(0x95_u32), FieldElement::ZERO])
+    });
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    let error = block_on(env.interact(cairo_short_string_to_felt("Door").unwrap())).unwrap_err();
+
+    assert_eq!(error.kind, DojoErrorKind::Fee);
+    assert!(rpc.requests("starknet_call")[0]
+        .to_string()
+        .contains(ACCOUNT_ADDRESS));
+    assert!(rpc.requests("starknet_addInvokeTransaction").is_empty());
+}
+
+#[test]
+fn account_info_reads_nonce_and_balance() {
+    let rpc = MockRpc::katana();
+    rpc.on("starknet_getNonce", |_| Ok(json!("0x5")));
+    let env = DojoEnv::from_config(rpc.config()).unwrap();
+
+    let info = block_on(env.account_info()).unwrap();
+
+    assert_eq!(
+        info.address,
+        FieldElement::from_hex_be(ACCOUNT_ADDRESS).unwrap()
+    );
+    assert_eq!(info.nonce, FieldElement::from(5_u32));
+    assert_eq!(
+        info.balance,
+        Some(FieldElement::from_hex_be(common::BALANCE).unwrap())
+    );
+}
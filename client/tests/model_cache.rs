@@ -0,0 +1,137 @@
+mod common;
+
+use async_compat::Compat;
+use common::{MockRpc, ACCOUNT_ADDRESS};
+use futures_lite::future;
+use game_demo::{
+    error::DojoErrorKind,
+    model_cache::ModelCache,
+    models::Game,
+    retry::{RetryLog, RetryPolicy},
+    transport::DojoTransport,
+};
+use serde_json::json;
+use starknet::{core::types::FieldElement, providers::JsonRpcClient};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn block_on<T>(task: impl std::future::Future<Output = T>) -> T {
+    future::block_on(Compat::new(task))
+}
+
+fn player() -> FieldElement {
+    FieldElement::from_hex_be(ACCOUNT_ADDRESS).unwrap()
+}
+
+// `Game` as the contract declares it, with `is_finished` of type `finished`
+fn declare_game(rpc: &MockRpc, finished: &'static str) {
+    rpc.model(
+        "Game",
+        &[("player", "ContractAddress", player())],
+        &[
+            ("game_id", "u32", FieldElement::from(3_u32)),
+            ("start_time", "u64", FieldElement::from(1_700_000_000_u64)),
+            ("turns_remaining", "u64", FieldElement::from(10_u32)),
+            ("is_finished", finished, FieldElement::ZERO),
+        ],
+    );
+}
+
+struct Reader {
+    cache: ModelCache,
+    retry: RetryPolicy,
+    log: RetryLog,
+}
+
+impl Reader {
+    fn new(rpc: &MockRpc) -> Self {
+        let config = rpc.config();
+        Self {
+            cache: ModelCache::new(config.world_address, DojoTransport::http(rpc.url())),
+            retry: config.retry,
+            log: RetryLog::default(),
+        }
+    }
+
+    fn game(&self) -> Result<Game, DojoErrorKind> {
+        block_on(self.cache.read::<Game>(&[player()], &self.retry, &self.log)).map_err(|e| e.kind)
+    }
+}
+
+#[test]
+fn model_class_is_reused_across_reads() {
+    let rpc = MockRpc::katana();
+    declare_game(&rpc, "bool");
+    let reader = Reader::new(&rpc);
+
+    let first = reader.game().unwrap();
+    let first_calls = rpc.requests("starknet_call").len();
+    let second = reader.game().unwrap();
+    let second_calls = rpc.requests("starknet_call").len() - first_calls;
+
+    assert_eq!(first, second);
+    assert_eq!(first.turns_remaining, 10);
+    assert!(first_calls > 1);
+    // the packed entity alone
+    assert_eq!(second_calls, 1);
+    assert_eq!(rpc.calls("schema"), 1);
+    assert_eq!(rpc.calls("layout"), 1);
+    assert_eq!(rpc.calls("entity"), 2);
+}
+
+#[test]
+fn model_that_no_longer_decodes_is_loaded_again() {
+    let rpc = MockRpc::katana();
+    declare_game(&rpc, "u32");
+    let reader = Reader::new(&rpc);
+
+    assert_eq!(reader.game(), Err(DojoErrorKind::Decode));
+    assert_eq!(reader.game(), Err(DojoErrorKind::Decode));
+
+    assert_eq!(rpc.calls("schema"), 2);
+}
+
+#[test]
+fn model_registered_again_is_loaded_again() {
+    let rpc = MockRpc::katana();
+    declare_game(&rpc, "bool");
+    rpc.on("starknet_getClassHashAt", |_| Ok(json!("0x10")));
+    let reader = Reader::new(&rpc);
+    let provider = JsonRpcClient::new(DojoTransport::http(rpc.url()));
+    reader.game().unwrap();
+
+    // the same class is kept
+    assert!(!block_on(reader.cache.check_upgrade(&provider)).unwrap());
+    reader.game().unwrap();
+    assert_eq!(rpc.calls("schema"), 1);
+
+    declare_game(&rpc, "bool");
+    assert!(!block_on(reader.cache.check_upgrade(&provider)).unwrap());
+    reader.game().unwrap();
+
+    assert_eq!(rpc.calls("schema"), 2);
+}
+
+#[test]
+fn world_upgrade_drops_every_model_class() {
+    let rpc = MockRpc::katana();
+    declare_game(&rpc, "bool");
+    let checks = AtomicUsize::new(0);
+    rpc.on("starknet_getClassHashAt", move |_| {
+        let class_hash = 0x10 + checks.fetch_add(1, Ordering::SeqCst).min(1);
+        Ok(json!(format!("{:#x}", class_hash)))
+    });
+    let reader = Reader::new(&rpc);
+    let provider = JsonRpcClient::new(DojoTransport::http(rpc.url()));
+
+    assert!(!block_on(reader.cache.check_upgrade(&provider)).unwrap());
+    reader.game().unwrap();
+    reader.game().unwrap();
+    assert_eq!(rpc.calls("schema"), 1);
+
+    assert!(block_on(reader.cache.check_upgrade(&provider)).unwrap());
+    // the same class hash is not an upgrade
+    assert!(!block_on(reader.cache.check_upgrade(&provider)).unwrap());
+    reader.game().unwrap();
+
+    assert_eq!(rpc.calls("schema"), 2);
+}
@@ -0,0 +1,32 @@
+use game_demo::{
+    error::DojoErrorKind,
+    models::{Door, Game, KeyBuilder, Object},
+};
+use starknet::core::types::FieldElement;
+
+fn player() -> FieldElement {
+    FieldElement::from_hex_be("0x517ececd29116499f4a1b64b094da79ba08dfd54a3edaa316134c41f8160973")
+        .unwrap()
+}
+
+#[test]
+fn keys_follow_the_model_layout() {
+    let player = player();
+    let object_id = FieldElement::from(42_u32);
+    let keys = KeyBuilder::new(player).object_id(object_id).game_id(7);
+
+    assert_eq!(keys.build::<Game>().unwrap(), vec![player]);
+    assert_eq!(keys.build::<Object>().unwrap(), vec![player, object_id]);
+    assert_eq!(
+        keys.build::<Door>().unwrap(),
+        vec![FieldElement::from(7_u32), player]
+    );
+}
+
+#[test]
+fn missing_key_is_an_encoding_error() {
+    let error = KeyBuilder::new(player()).build::<Door>().unwrap_err();
+
+    assert_eq!(error.kind, DojoErrorKind::Encoding);
+    assert!(error.message.contains("game_id"));
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use async_compat::Compat;
+use common::{felt_hex, world_event, MockRpc, ACCOUNT_ADDRESS};
+use futures_lite::future;
+use game_demo::{backend::GameBackend, error::DojoErrorKind, spectator::SpectatorBackend};
+use serde_json::json;
+use starknet::core::{types::FieldElement, utils::cairo_short_string_to_felt};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+fn block_on<T>(task: impl std::future::Future<Output = T>) -> T {
+    future::block_on(Compat::new(task))
+}
+
+fn player() -> FieldElement {
+    FieldElement::from_hex_be("0x1234").unwrap()
+}
+
+fn account() -> FieldElement {
+    FieldElement::from_hex_be(ACCOUNT_ADDRESS).unwrap()
+}
+
+#[test]
+fn actions_are_refused_without_a_call() {
+    let rpc = MockRpc::katana();
+    let spectator = SpectatorBackend::from_config(rpc.config(), player()).unwrap();
+
+    let error =
+        block_on(spectator.interact(cairo_short_string_to_felt("Door").unwrap())).unwrap_err();
+
+    assert_eq!(error.kind, DojoErrorKind::ReadOnly);
+    assert!(rpc.requests("starknet_estimateFee").is_empty());
+    assert!(rpc.requests("starknet_addInvokeTransaction").is_empty());
+}
+
+#[test]
+fn inspections_of_the_player_are_followed() {
+    let rpc = MockRpc::katana();
+    let block_number = Arc::new(AtomicU64::new(1));
+    let latest = block_number.clone();
+    rpc.on("starknet_blockNumber", move |_| {
+        Ok(json!(latest.load(Ordering::SeqCst)))
+    });
+    let bookcase = cairo_short_string_to_felt("Bookcase").unwrap();
+    let painting = cairo_short_string_to_felt("Painting").unwrap();
+    // the node filters on the player key, bookcase is inspected by the spectated player,
+    // painting by someone else
+    rpc.on("starknet_getEvents", move |params| {
+        let filter = params.get("filter").or_else(|| params.get(0));
+        let players = filter.map(|filter| filter["keys"][1].clone());
+        let events: Vec<_> = [(bookcase, player()), (painting, account())]
+            .into_iter()
+            .filter(|(_, sender)| players == Some(json!([felt_hex(*sender)])))
+            .map(|(object_id, sender)| {
+                let mut event = world_event("ObjectData", &[object_id, FieldElement::ONE]);
+                let keys = event["keys"].as_array_mut().unwrap();
+                keys.push(json!(felt_hex(sender)));
+                event["block_hash"] = json!("0x1");
+                event["block_number"] = json!(2);
+                event["transaction_hash"] = json!("0xa");
+                event
+            })
+            .collect();
+        Ok(json!({ "events": events, "continuation_token": null }))
+    });
+    let spectator = SpectatorBackend::from_config(rpc.config(), player()).unwrap();
+
+    // the first poll only marks where spectating started
+    assert!(block_on(spectator.inspected_objects()).unwrap().is_empty());
+    assert!(rpc.requests("starknet_getEvents").is_empty());
+
+    block_number.store(2, Ordering::SeqCst);
+    assert_eq!(
+        block_on(spectator.inspected_objects()).unwrap(),
+        vec![bookcase]
+    );
+
+    // nothing new until the next block
+    assert!(block_on(spectator.inspected_objects()).unwrap().is_empty());
+    assert_eq!(rpc.requests("starknet_getEvents").len(), 1);
+    assert!(rpc.requests("starknet_getTransactionByHash").is_empty());
+}
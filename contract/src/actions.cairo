@@ -1,5 +1,5 @@
 use dojo::world::{IWorldDispatcher, IWorldDispatcherTrait};
-use dojo_examples::models::{Game, GameTrait, Object, ObjectTrait, Door};
+use dojo_examples::models::{Game, GameTrait, Object, ObjectTrait, DescriptionWord, Door};
 use starknet::{ContractAddress, ClassHash};
 
 #[starknet::interface]
@@ -13,8 +13,11 @@ trait IActions<TContractState> {
 #[dojo::contract]
 mod actions {
     use super::IActions;
+    use array::ArrayTrait;
+    use option::OptionTrait;
+    use traits::TryInto;
     use starknet::{ContractAddress, get_caller_address, get_block_timestamp};
-    use dojo_examples::models::{Game, GameTrait, Object, ObjectTrait, Door};
+    use dojo_examples::models::{Game, GameTrait, Object, ObjectTrait, DescriptionWord, Door};
     #[event]
     use dojo_examples::events::{Event, ObjectData, GameState};
 
@@ -43,6 +46,8 @@ mod actions {
             emit!(world, GameState { game_state: 'Game Initialized' });
         }
 
+        // `objects_description` holds one short string array per object, each laid out
+        // as its length followed by its words
         fn spawn_object(self: @ContractState, mut objects_id: Array<felt252>, mut objects_description: Array<felt252>) {
 
             let world = self.world_dispatcher.read();
@@ -50,11 +55,9 @@ mod actions {
             let game = get!(world, player, (Game));
 
             let obj_id_len = objects_id.len();
-            let obj_desc_len = objects_description.len();
-
-            assert(obj_id_len == obj_desc_len, 'Array not equal.');
 
             let mut i: u32 = 0;
+            let mut offset: u32 = 0;
 
             loop {
                 if i == obj_id_len {
@@ -62,7 +65,30 @@ mod actions {
                 };
 
                    let obj_id = *objects_id.at(i);
-                   let obj_desc = *objects_description.at(i);
+                   let description_len: u32 = (*objects_description.at(offset)).try_into().unwrap();
+                   offset += 1;
+
+                   let mut index: u32 = 0;
+                   loop {
+                       if index == description_len {
+                           break;
+                       };
+
+                       set!(
+                           world,
+                           (
+                               DescriptionWord {
+                                   player: player,
+                                   object_id: obj_id,
+                                   index: index,
+                                   word: *objects_description.at(offset),
+                               },
+                           )
+                       );
+
+                       offset += 1;
+                       index += 1;
+                   };
 
                     set!(
                         world,
@@ -71,13 +97,15 @@ mod actions {
                                 player: player,
                                 object_id: obj_id,
                                 game_id: game.game_id,
-                                description: obj_desc,
+                                description_len: description_len,
                             },
                         )
                     );
 
                 i += 1;
-            }
+            };
+
+            assert(offset == objects_description.len(), 'Array not equal.');
 
         // TODO: Create Object Spawn event
         // emit!(world, GameState { game_state: 'Game Initialized' });
@@ -104,13 +132,23 @@ mod actions {
 
             let object = get!(world, (player, object_id).into(), Object);
 
+            let mut description = ArrayTrait::new();
+            let mut index: u32 = 0;
+            loop {
+                if index == object.description_len {
+                    break;
+                };
+
+                let word = get!(world, (player, object_id, index).into(), DescriptionWord);
+                description.append(word.word);
+                index += 1;
+            };
+
             set!(world, (game,));
 
             // emit item data
             emit!(world, GameState { game_state: 'Checking Item' });
-            emit!(
-                world, ObjectData { object_id: object.object_id, description: object.description }
-            );
+            emit!(world, ObjectData { player, object_id: object.object_id, description });
         }
 
         fn escape(self: @ContractState, secret: felt252) {
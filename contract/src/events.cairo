@@ -1,14 +1,20 @@
+use starknet::ContractAddress;
+
 #[event]
-#[derive(Copy, Drop, starknet::Event)]
+#[derive(Drop, starknet::Event)]
 enum Event {
     ObjectData: ObjectData,
     GameState: GameState,
 }
 
-#[derive(Drop, Copy, Serde, starknet::Event)]
+#[derive(Drop, Serde, starknet::Event)]
 struct ObjectData {
+    // lets spectators ask the node for the events of one player only
+    #[key]
+    player: ContractAddress,
     object_id: felt252,
-    description: felt252,
+    // short strings of up to 31 characters, joined in order
+    description: Array<felt252>,
 }
 
 #[derive(Drop, Copy, Serde, starknet::Event)]
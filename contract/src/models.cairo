@@ -14,6 +14,8 @@ struct Game {
 }
 
 
+// the description is kept in `description_len` DescriptionWord models, as models
+// cannot hold arrays
 #[derive(Model, Copy, Drop, Serde)]
 struct Object {
     #[key]
@@ -21,7 +23,19 @@ struct Object {
     #[key]
     object_id: felt252,
     game_id: u32,
-    description: felt252,
+    description_len: u32,
+}
+
+// one 31 character short string of an object's description
+#[derive(Model, Copy, Drop, Serde)]
+struct DescriptionWord {
+    #[key]
+    player: ContractAddress,
+    #[key]
+    object_id: felt252,
+    #[key]
+    index: u32,
+    word: felt252,
 }
 
 #[derive(Model, Copy, Drop, Serde)]